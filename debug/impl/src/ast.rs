@@ -0,0 +1,179 @@
+use crate::attr;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Generics, Ident, Index, Member, Result, Type,
+};
+
+pub struct Input<'a> {
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    pub attrs: attr::Container,
+    pub data: Body<'a>,
+}
+
+pub enum Body<'a> {
+    Struct(Variant<'a>),
+    Enum(Vec<Variant<'a>>),
+    // The fields of a union cannot be read without knowing which one is
+    // active, so none of them are represented.
+    Union,
+}
+
+// For a struct, the single "variant" carries the name and attributes of the
+// struct itself.
+pub struct Variant<'a> {
+    pub ident: &'a Ident,
+    pub attrs: attr::Variant,
+    pub original: &'a [Attribute],
+    pub path: TokenStream,
+    pub style: Style,
+    pub fields: Vec<Field<'a>>,
+}
+
+#[derive(Copy, Clone)]
+pub enum Style {
+    Named,
+    Tuple,
+    Unit,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    pub binding: Ident,
+    pub ty: &'a Type,
+    pub attrs: attr::Field,
+}
+
+impl<'a> Input<'a> {
+    pub fn from_syn(input: &'a DeriveInput) -> Result<Self> {
+        let attrs = attr::container(&input.attrs)?;
+        let data = match &input.data {
+            Data::Struct(data) => Body::Struct(Variant::from_syn(
+                &input.ident,
+                attr::Variant {
+                    rename: attrs.rename.clone(),
+                    fmt: attrs.fmt.clone(),
+                },
+                &input.attrs,
+                quote!(Self),
+                &data.fields,
+            )?),
+            Data::Enum(data) => {
+                if let (Some(rename), false) = (&attrs.rename, attrs.opaque) {
+                    return Err(Error::new_spanned(
+                        rename,
+                        "the name of an enum is not printed; rename its variants instead",
+                    ));
+                }
+                if let Some(fmt) = &attrs.fmt {
+                    return Err(Error::new_spanned(
+                        fmt,
+                        "#[debug(fmt = \"...\")] goes on each variant of an enum",
+                    ));
+                }
+                Body::Enum(
+                    data.variants
+                        .iter()
+                        .map(|variant| {
+                            let ident = &variant.ident;
+                            Variant::from_syn(
+                                ident,
+                                attr::variant(&variant.attrs)?,
+                                &variant.attrs,
+                                quote!(Self::#ident),
+                                &variant.fields,
+                            )
+                        })
+                        .collect::<Result<_>>()?,
+                )
+            }
+            Data::Union(_) => Body::Union,
+        };
+        Ok(Input {
+            ident: &input.ident,
+            generics: &input.generics,
+            attrs,
+            data,
+        })
+    }
+
+    pub fn variants(&self) -> &[Variant<'a>] {
+        match &self.data {
+            Body::Struct(variant) => std::slice::from_ref(variant),
+            Body::Enum(variants) => variants,
+            Body::Union => &[],
+        }
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &Field<'a>> {
+        self.variants().iter().flat_map(|variant| &variant.fields)
+    }
+}
+
+impl<'a> Variant<'a> {
+    fn from_syn(
+        ident: &'a Ident,
+        attrs: attr::Variant,
+        original: &'a [Attribute],
+        path: TokenStream,
+        fields: &'a Fields,
+    ) -> Result<Self> {
+        let style = match fields {
+            Fields::Named(_) => Style::Named,
+            Fields::Unnamed(_) => Style::Tuple,
+            Fields::Unit => Style::Unit,
+        };
+        let fields = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                Ok(Field {
+                    member: match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(Index::from(i)),
+                    },
+                    binding: format_ident!("__field{}", i),
+                    ty: &field.ty,
+                    attrs: attr::field(field)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Variant {
+            ident,
+            attrs,
+            original,
+            path,
+            style,
+            fields,
+        })
+    }
+
+    // A pattern that binds each of the given fields to its binding. The braced
+    // form `Path { 0: __field0, .. }` is accepted for every kind of struct and
+    // variant, including tuple and unit ones.
+    pub fn pattern<'f>(&self, fields: impl IntoIterator<Item = &'f Field<'f>>) -> TokenStream
+    where
+        'a: 'f,
+    {
+        self.pattern_with(fields, |field| field.binding.clone())
+    }
+
+    // Same as `pattern` but binds each field to a different name, for matching
+    // on two values of the same type at once.
+    pub fn pattern_with<'f>(
+        &self,
+        fields: impl IntoIterator<Item = &'f Field<'f>>,
+        binding: impl Fn(&Field) -> Ident,
+    ) -> TokenStream
+    where
+        'a: 'f,
+    {
+        let path = &self.path;
+        let (members, bindings): (Vec<_>, Vec<_>) = fields
+            .into_iter()
+            .map(|field| (&field.member, binding(field)))
+            .unzip();
+        quote!(#path { #(#members: #bindings,)* .. })
+    }
+}
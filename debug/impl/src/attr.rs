@@ -0,0 +1,454 @@
+use proc_macro2::Span;
+use syn::meta::ParseNestedMeta;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, Expr, ExprLit, ExprPath, Ident, Lit, LitInt, LitStr, Meta, Result, Token,
+    WherePredicate,
+};
+
+pub struct Container {
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    pub transparent: Option<Span>,
+    pub compact: bool,
+    pub rename: Option<LitStr>,
+    // Print only the name of the type. Always the case for unions.
+    pub opaque: bool,
+    // Print the raw bytes of the value after its name, which requires the type
+    // to implement the unsafe AsBytes trait. Implies `opaque`.
+    pub bytes: bool,
+    pub fmt: Option<LitStr>,
+    // How many levels of nested CustomDebug values to print, counting this
+    // one, before cutting the output short with `..`.
+    pub max_depth: Option<LitInt>,
+}
+
+pub struct Variant {
+    pub rename: Option<LitStr>,
+    // A template like "Point({x}, {y})" that replaces the usual output.
+    pub fmt: Option<LitStr>,
+}
+
+pub struct Field {
+    pub format: Option<LitStr>,
+    pub with: Option<ExprPath>,
+    pub rename: Option<LitStr>,
+    // Replaces the bounds that would be inferred from this field's type. An
+    // empty string means the field needs no bounds at all.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    pub max_items: Option<LitInt>,
+    pub max_len: Option<LitInt>,
+    // A format string applied to every element of a collection, or to every
+    // value of a map.
+    pub each: Option<LitStr>,
+    pub skip_if: Option<ExprPath>,
+    // Format closures through Debug like any other field, rather than with a
+    // placeholder.
+    pub no_placeholder: bool,
+}
+
+pub fn container(attrs: &[Attribute]) -> Result<Container> {
+    let mut container = Container {
+        bound: None,
+        transparent: None,
+        compact: false,
+        rename: None,
+        opaque: false,
+        bytes: false,
+        fmt: None,
+        max_depth: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                once(&meta, &container.bound)?;
+                let bound: LitStr = meta.value()?.parse()?;
+                container.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+                Ok(())
+            } else if meta.path.is_ident("transparent") {
+                once(&meta, &container.transparent)?;
+                container.transparent = Some(meta.path.span());
+                Ok(())
+            } else if meta.path.is_ident("compact") {
+                flag(&meta, &mut container.compact)
+            } else if meta.path.is_ident("rename") {
+                once(&meta, &container.rename)?;
+                container.rename = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("opaque") {
+                flag(&meta, &mut container.opaque)
+            } else if meta.path.is_ident("bytes") {
+                flag(&meta, &mut container.bytes)
+            } else if meta.path.is_ident("fmt") {
+                once(&meta, &container.fmt)?;
+                container.fmt = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("max_depth") {
+                once(&meta, &container.max_depth)?;
+                container.max_depth = Some(parse_limit(meta.value()?)?);
+                Ok(())
+            } else {
+                Err(unknown(
+                    &meta,
+                    &[
+                        "bound",
+                        "transparent",
+                        "compact",
+                        "rename",
+                        "opaque",
+                        "bytes",
+                        "fmt",
+                        "max_depth",
+                    ],
+                ))
+            }
+        })?;
+    }
+    container.opaque |= container.bytes;
+
+    if let (Some(span), true) = (container.transparent, container.opaque) {
+        return Err(Error::new(
+            span,
+            "#[debug(transparent)] cannot be combined with #[debug(opaque)]",
+        ));
+    }
+    if let (Some(fmt), true) = (&container.fmt, container.opaque) {
+        return Err(Error::new_spanned(
+            fmt,
+            "#[debug(fmt = \"...\")] cannot be combined with #[debug(opaque)]",
+        ));
+    }
+    if let (Some(fmt), Some(_)) = (&container.fmt, container.transparent) {
+        return Err(Error::new_spanned(
+            fmt,
+            "#[debug(fmt = \"...\")] cannot be combined with #[debug(transparent)]",
+        ));
+    }
+    if let (Some(max_depth), Some(_)) = (&container.max_depth, container.transparent) {
+        return Err(Error::new(
+            max_depth.span(),
+            "#[debug(max_depth = ...)] cannot be combined with #[debug(transparent)]",
+        ));
+    }
+
+    Ok(container)
+}
+
+pub fn variant(attrs: &[Attribute]) -> Result<Variant> {
+    let mut variant = Variant {
+        rename: None,
+        fmt: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                once(&meta, &variant.rename)?;
+                variant.rename = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("fmt") {
+                once(&meta, &variant.fmt)?;
+                variant.fmt = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(unknown(&meta, &["rename", "fmt"]))
+            }
+        })?;
+    }
+
+    Ok(variant)
+}
+
+pub fn field(original: &syn::Field) -> Result<Field> {
+    let mut field = Field {
+        format: None,
+        with: None,
+        rename: None,
+        bound: None,
+        max_items: None,
+        max_len: None,
+        each: None,
+        skip_if: None,
+        no_placeholder: false,
+    };
+
+    for attr in &original.attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(format),
+                    ..
+                }) => {
+                    if field.format.is_some() {
+                        return Err(Error::new_spanned(
+                            attr,
+                            "duplicate #[debug = \"...\"] attribute",
+                        ));
+                    }
+                    check_format(format)?;
+                    field.format = Some(format.clone());
+                }
+                value => {
+                    return Err(Error::new_spanned(
+                        value,
+                        "expected a format string, like #[debug = \"{:#x}\"]",
+                    ))
+                }
+            },
+            _ => attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("with") {
+                    once(&meta, &field.with)?;
+                    let with: LitStr = meta.value()?.parse()?;
+                    field.with = Some(with.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    once(&meta, &field.rename)?;
+                    field.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("bound") {
+                    once(&meta, &field.bound)?;
+                    let bound: LitStr = meta.value()?.parse()?;
+                    field.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+                    Ok(())
+                } else if meta.path.is_ident("max_items") {
+                    once(&meta, &field.max_items)?;
+                    field.max_items = Some(parse_limit(meta.value()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("max_len") {
+                    once(&meta, &field.max_len)?;
+                    field.max_len = Some(parse_limit(meta.value()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("each") {
+                    once(&meta, &field.each)?;
+                    let each: LitStr = meta.value()?.parse()?;
+                    check_format(&each)?;
+                    field.each = Some(each);
+                    Ok(())
+                } else if meta.path.is_ident("skip_if") {
+                    once(&meta, &field.skip_if)?;
+                    let skip_if: LitStr = meta.value()?.parse()?;
+                    field.skip_if = Some(skip_if.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("no_placeholder") {
+                    flag(&meta, &mut field.no_placeholder)
+                } else {
+                    Err(unknown(
+                        &meta,
+                        &[
+                            "with",
+                            "rename",
+                            "bound",
+                            "max_items",
+                            "max_len",
+                            "each",
+                            "skip_if",
+                            "no_placeholder",
+                        ],
+                    ))
+                }
+            })?,
+        }
+    }
+
+    // Tuple fields print without names, so there is nothing to rename.
+    if let (Some(rename), None) = (&field.rename, &original.ident) {
+        return Err(Error::new_spanned(
+            rename,
+            "#[debug(rename = \"...\")] is only supported on named fields",
+        ));
+    }
+
+    // Each of these replaces how the value of the field is printed, so at most
+    // one of them can apply.
+    let modes = [
+        (
+            field.format.as_ref().map(Spanned::span),
+            "#[debug = \"...\"]",
+        ),
+        (
+            field.with.as_ref().map(Spanned::span),
+            "#[debug(with = \"...\")]",
+        ),
+        (
+            field.max_items.as_ref().map(LitInt::span),
+            "#[debug(max_items = ...)]",
+        ),
+        (
+            field.max_len.as_ref().map(LitInt::span),
+            "#[debug(max_len = ...)]",
+        ),
+        (
+            field.each.as_ref().map(Spanned::span),
+            "#[debug(each = \"...\")]",
+        ),
+    ];
+    let mut modes = modes
+        .iter()
+        .filter_map(|(span, name)| Some((span.as_ref()?, name)));
+    if let (Some((_, first)), Some((span, second))) = (modes.next(), modes.next()) {
+        return Err(Error::new(
+            *span,
+            format!("{} cannot be combined with {}", second, first),
+        ));
+    }
+
+    Ok(field)
+}
+
+// Each attribute may be given at most once per item, whether within one
+// #[debug(...)] or spread across several.
+fn once<T>(meta: &ParseNestedMeta, slot: &Option<T>) -> Result<()> {
+    match slot {
+        Some(_) => Err(duplicate(meta)),
+        None => Ok(()),
+    }
+}
+
+fn flag(meta: &ParseNestedMeta, flag: &mut bool) -> Result<()> {
+    if *flag {
+        return Err(duplicate(meta));
+    }
+    *flag = true;
+    Ok(())
+}
+
+fn duplicate(meta: &ParseNestedMeta) -> Error {
+    Error::new_spanned(
+        &meta.path,
+        format!("duplicate #[debug({})] attribute", path_name(meta)),
+    )
+}
+
+fn unknown(meta: &ParseNestedMeta, expected: &[&str]) -> Error {
+    let expected = expected
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Error::new_spanned(
+        &meta.path,
+        format!(
+            "unknown debug attribute `{}`, expected one of {}",
+            path_name(meta),
+            expected,
+        ),
+    )
+}
+
+fn path_name(meta: &ParseNestedMeta) -> String {
+    let segments = meta.path.segments.iter();
+    let names: Vec<String> = segments.map(|segment| segment.ident.to_string()).collect();
+    names.join("::")
+}
+
+// The format string of #[debug = "..."] is applied to the field's value alone,
+// and that of #[debug(each = "...")] to one element, so it needs exactly one
+// placeholder and that placeholder cannot name some other argument.
+fn check_format(format: &LitStr) -> Result<()> {
+    let value = format.value();
+    let mut chars = value.chars().peekable();
+    let mut placeholders = 0;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '}' if chars.peek() == Some(&ch) => {
+                chars.next();
+            }
+            '{' => {
+                placeholders += 1;
+                let spec: String = chars.by_ref().take_while(|&ch| ch != '}').collect();
+                let (arg, rest) = spec.split_at(spec.find(':').unwrap_or(spec.len()));
+                if !arg.trim().is_empty() && arg.trim() != "0" {
+                    return Err(Error::new_spanned(
+                        format,
+                        format!(
+                            "the field's value is the only format argument; \
+                             use `{{{}}}` instead of `{{{}}}`",
+                            rest, spec,
+                        ),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    if placeholders != 1 {
+        return Err(Error::new_spanned(
+            format,
+            format!(
+                "format string must contain exactly one `{{}}` placeholder for the field's value, found {}",
+                placeholders,
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// A non-negative integer, stripped of any suffix so that it can be used as a
+// usize in the generated code.
+fn parse_limit(input: ParseStream) -> Result<LitInt> {
+    let limit: LitInt = input.parse()?;
+    limit.base10_parse::<usize>()?;
+    Ok(LitInt::new(limit.base10_digits(), limit.span()))
+}
+
+pub struct Display {
+    pub template: Option<LitStr>,
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+}
+
+// Parses `#[display("template")]` and `#[display(bound = "...")]`.
+pub fn display(attrs: &[Attribute]) -> Result<Display> {
+    let mut display = Display {
+        template: None,
+        bound: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("display") {
+            continue;
+        }
+        attr.parse_args_with(|input: ParseStream| {
+            if input.peek(LitStr) {
+                let template: LitStr = input.parse()?;
+                if display.template.is_some() {
+                    return Err(Error::new_spanned(template, "duplicate display template"));
+                }
+                display.template = Some(template);
+                return Ok(());
+            }
+            let ident: Ident = input.parse()?;
+            if ident != "bound" {
+                return Err(Error::new_spanned(
+                    &ident,
+                    format!(
+                        "unknown display attribute `{}`, expected a template string or `bound`",
+                        ident,
+                    ),
+                ));
+            }
+            if display.bound.is_some() {
+                return Err(Error::new_spanned(
+                    ident,
+                    "duplicate #[display(bound)] attribute",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            let bound: LitStr = input.parse()?;
+            display.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+            Ok(())
+        })?;
+    }
+
+    Ok(display)
+}
@@ -0,0 +1,125 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::collections::BTreeSet;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, Expr, Generics, Ident, Type, TypeBareFn, TypePath, TypeTraitObject, WherePredicate,
+};
+
+// Infers the where-clause predicates needed for each type to implement the
+// formatting trait it is paired with.
+//
+// A type parameter is bounded only if the types mention it somewhere its own
+// impl could matter. Mentions inside PhantomData, function pointers and trait
+// objects never do, since those implement the formatting traits regardless of
+// their parameters. Neither do const expressions, like the length of `[T; N]`
+// or the argument in `Buffer<{ N + 1 }>`, which are never formatted. Lifetimes
+// and const parameters are never bounded. Associated types like `T::Value` or
+// `<T as Trait>::Item` are bounded as a whole rather than through their
+// parameter.
+pub fn infer<'a>(
+    generics: &Generics,
+    uses: impl IntoIterator<Item = (&'a Type, TokenStream)>,
+) -> Vec<WherePredicate> {
+    // Group the types by trait so that each distinct bound is inferred once.
+    let mut groups: Vec<(String, TokenStream, Vec<&Type>)> = Vec::new();
+    for (ty, bound) in uses {
+        let key = bound.to_string();
+        match groups.iter_mut().find(|group| group.0 == key) {
+            Some(group) => group.2.push(ty),
+            None => groups.push((key, bound, vec![ty])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .flat_map(|(_, bound, types)| infer_bound(generics, types, &bound))
+        .collect()
+}
+
+fn infer_bound(generics: &Generics, types: Vec<&Type>, bound: &TokenStream) -> Vec<WherePredicate> {
+    let params: Vec<&Ident> = generics.type_params().map(|param| &param.ident).collect();
+    let mut visitor = FindParams {
+        params: &params,
+        relevant: BTreeSet::new(),
+        associated: Vec::new(),
+    };
+    for ty in types {
+        visitor.visit_type(ty);
+    }
+
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    for (i, param) in params.iter().enumerate() {
+        if visitor.relevant.contains(&i) {
+            predicates.push(parse_quote!(#param: #bound));
+        }
+    }
+    for ty in visitor.associated {
+        predicates.push(parse_quote!(#ty: #bound));
+    }
+    predicates
+}
+
+struct FindParams<'a> {
+    params: &'a [&'a Ident],
+    // Indices into `params`, so that bounds come out in declaration order.
+    relevant: BTreeSet<usize>,
+    associated: Vec<TypePath>,
+}
+
+impl FindParams<'_> {
+    fn add_associated(&mut self, ty: &TypePath) {
+        let repr = ty.to_token_stream().to_string();
+        if !self
+            .associated
+            .iter()
+            .any(|prev| prev.to_token_stream().to_string() == repr)
+        {
+            self.associated.push(ty.clone());
+        }
+    }
+
+    fn mentions_param(&self, ty: &TypePath) -> bool {
+        let mut visitor = FindParams {
+            params: self.params,
+            relevant: BTreeSet::new(),
+            associated: Vec::new(),
+        };
+        visit::visit_type_path(&mut visitor, ty);
+        !visitor.relevant.is_empty() || !visitor.associated.is_empty()
+    }
+}
+
+impl<'ast> Visit<'ast> for FindParams<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if ty.qself.is_some() {
+            if self.mentions_param(ty) {
+                self.add_associated(ty);
+            }
+            return;
+        }
+
+        let first = &ty.path.segments[0].ident;
+        if let Some(i) = self.params.iter().position(|param| *param == first) {
+            if ty.path.segments.len() == 1 {
+                self.relevant.insert(i);
+            } else {
+                self.add_associated(ty);
+            }
+            return;
+        }
+
+        let last = ty.path.segments.last().unwrap();
+        if last.ident == "PhantomData" {
+            return;
+        }
+
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_type_bare_fn(&mut self, _ty: &'ast TypeBareFn) {}
+
+    fn visit_type_trait_object(&mut self, _ty: &'ast TypeTraitObject) {}
+
+    fn visit_expr(&mut self, _expr: &'ast Expr) {}
+}
@@ -0,0 +1,105 @@
+use crate::ast::{Body, Field, Input, Variant};
+use crate::expand;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Ident, Result};
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = expand::where_clause(&input, false)?;
+
+    // Opaque types and unions have no fields that can be compared.
+    let opaque = input.attrs.opaque || matches!(input.data, Body::Union);
+    let body = if opaque || input.fields().next().is_none() && input.variants().len() < 2 {
+        quote!(let _ = __other;)
+    } else {
+        let arms = input.variants().iter().map(diff_variant);
+        let mismatch = if input.variants().len() > 1 {
+            let names = input.variants().iter().map(|variant| {
+                let pattern = variant.pattern([]);
+                let name = expand::variant_name(variant);
+                quote!(#pattern => #name,)
+            });
+            quote! {
+                _ => {
+                    let __name = |value: &Self| match value {
+                        #(#names)*
+                    };
+                    __diff.push_str(&::derive_debug::__private::format!("- {}\n+ {}\n", __name(self), __name(__other)));
+                }
+            }
+        } else {
+            quote!()
+        };
+        quote! {
+            match (self, __other) {
+                #(#arms)*
+                #mismatch
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::derive_debug::DebugDiff for #ident #ty_generics #where_clause {
+            fn debug_diff(&self, __other: &Self) -> ::derive_debug::__private::String {
+                let mut __diff = ::derive_debug::__private::String::new();
+                #body
+                __diff
+            }
+        }
+    })
+}
+
+fn diff_variant(variant: &Variant) -> TokenStream {
+    let fields: Vec<&Field> = expand::read_fields(variant).collect();
+    let left = variant.pattern(fields.iter().copied());
+    let right = variant.pattern_with(fields.iter().copied(), other);
+
+    let fields = variant.fields.iter().map(|field| {
+        let name = expand::field_name(field);
+        let left = repr(field, &field.binding);
+        let right = repr(field, &other(field));
+        quote! {
+            let __left = #left;
+            let __right = #right;
+            if __left != __right {
+                if let ::core::option::Option::Some(__left) = __left {
+                    __diff.push_str(&::derive_debug::__private::format!("- {}: {}\n", #name, __left));
+                }
+                if let ::core::option::Option::Some(__right) = __right {
+                    __diff.push_str(&::derive_debug::__private::format!("+ {}: {}\n", #name, __right));
+                }
+            }
+        }
+    });
+
+    quote! {
+        (#left, #right) => {
+            #({ #fields })*
+        }
+    }
+}
+
+// The formatted value of the field, or None if it is skipped.
+fn repr(field: &Field, binding: &Ident) -> TokenStream {
+    let value = expand::field_value(field, binding);
+    let repr = quote!(::core::option::Option::Some(
+        ::derive_debug::__private::format!("{:?}", #value)
+    ));
+    match &field.attrs.skip_if {
+        Some(predicate) => quote! {
+            if #predicate(#binding) {
+                ::core::option::Option::None
+            } else {
+                #repr
+            }
+        },
+        None => repr,
+    }
+}
+
+fn other(field: &Field) -> Ident {
+    format_ident!("{}_other", field.binding)
+}
@@ -0,0 +1,94 @@
+use crate::ast::{Body, Input, Variant};
+use crate::attr;
+use crate::bound;
+use crate::template::Template;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Error, LitStr, Result};
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let container = attr::display(&input.attrs)?;
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    let templates = match &input.data {
+        Body::Struct(variant) => vec![template(container.template.as_ref(), variant)?],
+        Body::Enum(variants) => {
+            if let Some(template) = &container.template {
+                return Err(Error::new_spanned(
+                    template,
+                    "display templates go on each variant of an enum",
+                ));
+            }
+            variants
+                .iter()
+                .map(|variant| {
+                    let attrs = attr::display(variant.original)?;
+                    if let Some(bound) = &attrs.bound {
+                        return Err(Error::new_spanned(
+                            bound,
+                            "display bounds go on the enum, not on its variants",
+                        ));
+                    }
+                    template(attrs.template.as_ref(), variant)
+                })
+                .collect::<Result<_>>()?
+        }
+        Body::Union => {
+            return Err(Error::new_spanned(
+                input.ident,
+                "CustomDisplay does not support unions",
+            ));
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    match container.bound {
+        Some(bound) => where_clause.predicates.extend(bound),
+        None => where_clause.predicates.extend(bound::infer(
+            input.generics,
+            templates
+                .iter()
+                .flat_map(|template| &template.uses)
+                .map(|(field, format_trait)| (field.ty, format_trait.clone())),
+        )),
+    }
+
+    let body = if templates.is_empty() {
+        quote!(match *self {})
+    } else {
+        let arms = input
+            .variants()
+            .iter()
+            .zip(&templates)
+            .map(|(variant, template)| {
+                let pattern = variant.pattern(template.fields.iter().copied());
+                let format = &template.format;
+                let args = template.args();
+                quote! {
+                    #pattern => ::core::write!(__f, #format, #args),
+                }
+            });
+        quote!(match self { #(#arms)* })
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+fn template<'a>(template: Option<&LitStr>, variant: &'a Variant<'a>) -> Result<Template<'a>> {
+    match template {
+        Some(template) => Template::parse(template, variant),
+        None => Err(Error::new_spanned(
+            variant.ident,
+            "missing #[display(\"...\")] template",
+        )),
+    }
+}
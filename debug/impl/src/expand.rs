@@ -0,0 +1,449 @@
+use crate::ast::{Body, Field, Input, Style, Variant};
+use crate::bound;
+use crate::template::{self, Template};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{
+    DeriveInput, Error, GenericArgument, Ident, LitStr, Member, PathArguments, Result, Type,
+    TypeParamBound, WhereClause,
+};
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = where_clause(&input, true)?;
+
+    let mut body = if input.attrs.opaque || matches!(input.data, Body::Union) {
+        fmt_opaque(&input)
+    } else if let Some(span) = input.attrs.transparent {
+        fmt_transparent(&input, span)?
+    } else if input.variants().is_empty() {
+        quote!(match *self {})
+    } else {
+        let arms = input
+            .variants()
+            .iter()
+            .map(fmt_variant)
+            .collect::<Result<Vec<_>>>()?;
+        quote!(match self { #(#arms)* })
+    };
+
+    // Transparent types print exactly like their field, so they do not count
+    // as a level of nesting.
+    if input.attrs.transparent.is_none() {
+        let max_depth = match &input.attrs.max_depth {
+            Some(max_depth) => quote!(::core::option::Option::Some(#max_depth)),
+            None => quote!(::core::option::Option::None),
+        };
+        body = quote! {
+            let __depth = match ::derive_debug::__private::Depth::enter(#max_depth) {
+                ::core::option::Option::Some(depth) => depth,
+                ::core::option::Option::None => return __f.write_str(".."),
+            };
+            #body
+        };
+    }
+
+    if input.attrs.compact {
+        // Formatting through a fresh `{:?}` drops the alternate flag for this
+        // value and everything nested inside of it.
+        body = quote! {
+            if __f.alternate() {
+                return ::core::write!(__f, "{:?}", self);
+            }
+            #body
+        };
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, __f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+// The where clause of the Debug impl. Templates from #[debug(fmt = "...")] are
+// taken into account only if `templates` is set, since other impls format each
+// field on its own regardless of the template.
+pub fn where_clause(input: &Input, templates: bool) -> Result<WhereClause> {
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    if let Some(bound) = &input.attrs.bound {
+        where_clause.predicates.extend(bound.iter().cloned());
+        return Ok(where_clause.clone());
+    }
+    if input.attrs.opaque {
+        return Ok(where_clause.clone());
+    }
+
+    let mut inferred = Vec::new();
+    for variant in input.variants() {
+        for field in &variant.fields {
+            if let Some(bound) = &field.attrs.bound {
+                where_clause.predicates.extend(bound.iter().cloned());
+            }
+        }
+        match (&variant.attrs.fmt, templates) {
+            (Some(fmt), true) => {
+                let template = Template::parse(fmt, variant)?;
+                for (field, format_trait) in template.uses {
+                    if field.attrs.bound.is_none() {
+                        inferred.push((field.ty, format_trait));
+                    }
+                }
+            }
+            _ => {
+                for field in &variant.fields {
+                    if field.attrs.bound.is_some() {
+                        continue;
+                    }
+                    if let Some(each) = &field.attrs.each {
+                        inferred.extend(each_uses(field.ty, each));
+                    } else if field.attrs.with.is_none()
+                        && field.attrs.max_len.is_none()
+                        && placeholder(field).is_none()
+                    {
+                        inferred.push((field.ty, quote!(::core::fmt::Debug)));
+                    }
+                }
+            }
+        }
+    }
+    where_clause
+        .predicates
+        .extend(bound::infer(input.generics, inferred));
+    Ok(where_clause.clone())
+}
+
+fn fmt_opaque(input: &Input) -> TokenStream {
+    let name = match &input.attrs.rename {
+        Some(rename) => rename.value(),
+        None => input.ident.unraw().to_string(),
+    };
+
+    if !input.attrs.bytes {
+        return quote!(__f.debug_struct(#name).finish_non_exhaustive());
+    }
+
+    quote! {
+        // Requires an `unsafe impl AsBytes`, in which the user vouches that
+        // every byte of the value is initialized.
+        let bytes = ::derive_debug::__private::bytes(self);
+        __f.debug_tuple(#name)
+            .field(&::core::fmt::from_fn(|__f| {
+                for (i, byte) in bytes.iter().enumerate() {
+                    if i > 0 {
+                        __f.write_str(" ")?;
+                    }
+                    ::core::write!(__f, "{:02x}", byte)?;
+                }
+                ::core::result::Result::Ok(())
+            }))
+            .finish()
+    }
+}
+
+fn fmt_transparent(input: &Input, span: Span) -> Result<TokenStream> {
+    match &input.data {
+        Body::Struct(variant) if variant.fields.len() == 1 => {
+            let pattern = pattern(variant);
+            let value = field_value(&variant.fields[0], &variant.fields[0].binding);
+            Ok(quote! {
+                match self {
+                    #pattern => ::core::fmt::Debug::fmt(#value, __f),
+                }
+            })
+        }
+        _ => Err(Error::new(
+            span,
+            "#[debug(transparent)] requires a struct with exactly one field",
+        )),
+    }
+}
+
+// Produces one match arm that destructures the variant and formats it.
+fn fmt_variant(variant: &Variant) -> Result<TokenStream> {
+    if let Some(fmt) = &variant.attrs.fmt {
+        let template = Template::parse(fmt, variant)?;
+        let pattern = variant.pattern(template.fields.iter().copied());
+        let format = &template.format;
+        let args = template.args();
+        return Ok(quote! {
+            #pattern => ::core::write!(__f, #format, #args),
+        });
+    }
+
+    let name = variant_name(variant);
+    let pattern = pattern(variant);
+
+    let body = match variant.style {
+        Style::Named => {
+            let fields = variant.fields.iter().map(|field| {
+                let name = field_name(field);
+                let value = field_value(field, &field.binding);
+                skip_if(field, quote!(__builder.field(#name, #value);))
+            });
+            quote!({
+                let mut __builder = __f.debug_struct(#name);
+                #(#fields)*
+                __builder.finish()
+            })
+        }
+        Style::Tuple => {
+            let fields = variant.fields.iter().map(|field| {
+                let value = field_value(field, &field.binding);
+                skip_if(field, quote!(__builder.field(#value);))
+            });
+            quote!({
+                let mut __builder = __f.debug_tuple(#name);
+                #(#fields)*
+                __builder.finish()
+            })
+        }
+        Style::Unit => quote!(__f.write_str(#name)),
+    };
+
+    Ok(quote! {
+        #pattern => #body,
+    })
+}
+
+// Binds every field whose value is looked at when formatting the variant.
+pub fn pattern(variant: &Variant) -> TokenStream {
+    variant.pattern(read_fields(variant))
+}
+
+pub fn read_fields<'a>(variant: &'a Variant<'a>) -> impl Iterator<Item = &'a Field<'a>> {
+    variant
+        .fields
+        .iter()
+        .filter(|field| match placeholder(field) {
+            Some(placeholder) => placeholder.options > 0 || field.attrs.skip_if.is_some(),
+            None => true,
+        })
+}
+
+pub fn variant_name(variant: &Variant) -> String {
+    match &variant.attrs.rename {
+        Some(rename) => rename.value(),
+        None => variant.ident.unraw().to_string(),
+    }
+}
+
+pub fn field_name(field: &Field) -> String {
+    match (&field.attrs.rename, &field.member) {
+        (Some(rename), _) => rename.value(),
+        (None, Member::Named(ident)) => ident.unraw().to_string(),
+        (None, Member::Unnamed(index)) => index.index.to_string(),
+    }
+}
+
+// Guards the statement that prints a field with its skip_if predicate, if any.
+pub fn skip_if(field: &Field, stmt: TokenStream) -> TokenStream {
+    match &field.attrs.skip_if {
+        Some(predicate) => {
+            let binding = &field.binding;
+            quote! {
+                if !#predicate(#binding) {
+                    #stmt
+                }
+            }
+        }
+        None => stmt,
+    }
+}
+
+pub fn field_value(field: &Field, binding: &Ident) -> TokenStream {
+    if let Some(placeholder) = placeholder(field) {
+        let text = placeholder.text;
+        if placeholder.options == 0 {
+            return quote!(&::core::format_args!(#text));
+        }
+        // Maps each Option from the inside out, so that `Some(Some(<closure>))`
+        // keeps the shape of the value.
+        let mut value = quote!(::core::fmt::from_fn(|__f| __f.write_str(#text)));
+        for i in (0..placeholder.options).rev() {
+            let option = if i == 0 {
+                quote!(#binding)
+            } else {
+                quote!(__option)
+            };
+            let param = if i + 1 == placeholder.options {
+                quote!(_)
+            } else {
+                quote!(__option)
+            };
+            value = quote! {
+                ::core::option::Option::map(::core::option::Option::as_ref(#option), |#param| #value)
+            };
+        }
+        return quote!(&#value);
+    }
+    if let Some(with) = &field.attrs.with {
+        return quote!(&::core::fmt::from_fn(|__f| #with(#binding, __f)));
+    }
+    if let Some(max_items) = &field.attrs.max_items {
+        // Iterates over a reference to the field if possible, and over a clone
+        // of it otherwise. See `__private::Items`.
+        return quote! {
+            &::core::fmt::from_fn(|__f| {
+                use ::derive_debug::__private::{ItemsByRef as _, ItemsByValue as _};
+                (&&::derive_debug::__private::Items(#binding)).fmt_items(__f, #max_items)
+            })
+        };
+    }
+    if let Some(max_len) = &field.attrs.max_len {
+        return quote! {
+            &::core::fmt::from_fn(|__f| {
+                let string: &::core::primitive::str = ::core::convert::AsRef::as_ref(#binding);
+                match ::core::iter::Iterator::nth(&mut string.char_indices(), #max_len) {
+                    ::core::option::Option::Some((end, _)) => {
+                        ::core::fmt::Debug::fmt(&string[..end], __f)?;
+                        let rest = ::core::iter::Iterator::count(string[end..].chars());
+                        ::core::write!(__f, "... ({} more)", rest)
+                    }
+                    ::core::option::Option::None => ::core::fmt::Debug::fmt(string, __f),
+                }
+            })
+        };
+    }
+    if let Some(each) = &field.attrs.each {
+        // Prints a map if iterating yields key-value pairs, and a list
+        // otherwise. See `__private::Each`.
+        return quote! {
+            &::core::fmt::from_fn(|__f| {
+                use ::derive_debug::__private::{EachElement as _, EachEntry as _};
+                (&&::derive_debug::__private::Each(#binding.iter()))
+                    .fmt_each(__f, |__f, element| ::core::write!(__f, #each, element))
+            })
+        };
+    }
+    match &field.attrs.format {
+        Some(format) => quote!(&::core::format_args!(#format, #binding)),
+        None => quote!(#binding),
+    }
+}
+
+// The types that #[debug(each = "...")] needs bounded, going by the shape of
+// the collection: the element of a slice or array, or the type arguments of a
+// path like `Vec<T>` or `BTreeMap<K, V>`. With two or more arguments, the first
+// is taken for the key, which prints with Debug, and the second for the value,
+// which prints with the trait its placeholder asks for. Anything after that is
+// a hasher or allocator, which is never printed.
+fn each_uses<'a>(ty: &'a Type, each: &LitStr) -> Vec<(&'a Type, TokenStream)> {
+    let format = each.value();
+    let spec = format
+        .replace("{{", "")
+        .split_once('{')
+        .and_then(|(_, rest)| rest.split_once('}'))
+        .and_then(|(placeholder, _)| placeholder.split_once(':'))
+        .map_or(String::new(), |(_, spec)| spec.to_owned());
+    let format_trait = template::format_trait(&spec);
+
+    let mut ty = ty;
+    loop {
+        ty = match ty {
+            Type::Paren(ty) => &ty.elem,
+            Type::Group(ty) => &ty.elem,
+            Type::Reference(ty) => &ty.elem,
+            Type::Slice(ty) => return vec![(&ty.elem, format_trait)],
+            Type::Array(ty) => return vec![(&ty.elem, format_trait)],
+            Type::Path(path) if path.qself.is_none() => {
+                let last = path.path.segments.last().unwrap();
+                let args: Vec<&Type> = match &last.arguments {
+                    PathArguments::AngleBracketed(args) => args
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                return match args[..] {
+                    [] => vec![(ty, format_trait)],
+                    [elem] => vec![(elem, format_trait)],
+                    [key, value, ..] => {
+                        vec![(key, quote!(::core::fmt::Debug)), (value, format_trait)]
+                    }
+                };
+            }
+            _ => return vec![(ty, format_trait)],
+        };
+    }
+}
+
+// Closures never implement Debug, so fields holding one behind a pointer, like
+// `Box<dyn Fn()>` or `Option<Rc<dyn Fn()>>`, print a placeholder unless told
+// otherwise. Any other trait object might implement Debug through a supertrait
+// and is left alone.
+fn placeholder(field: &Field) -> Option<Placeholder> {
+    let attrs = &field.attrs;
+    if attrs.no_placeholder
+        || attrs.format.is_some()
+        || attrs.with.is_some()
+        || attrs.max_items.is_some()
+        || attrs.max_len.is_some()
+        || attrs.each.is_some()
+    {
+        return None;
+    }
+
+    // Raw pointers are not looked through, since they print their address.
+    let mut ty = field.ty;
+    let mut options = 0;
+    loop {
+        ty = match ty {
+            Type::Paren(ty) => &ty.elem,
+            Type::Group(ty) => &ty.elem,
+            Type::Reference(ty) => &ty.elem,
+            Type::Path(ty) if ty.qself.is_none() => {
+                let last = ty.path.segments.last().unwrap();
+                let is_option = last.ident == "Option";
+                let is_pointer = ["Box", "Rc", "Arc"].iter().any(|ptr| last.ident == ptr);
+                match &last.arguments {
+                    PathArguments::AngleBracketed(args)
+                        if (is_pointer || is_option) && args.args.len() == 1 =>
+                    {
+                        options += usize::from(is_option);
+                        match &args.args[0] {
+                            GenericArgument::Type(ty) => ty,
+                            _ => return None,
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            Type::TraitObject(ty) => {
+                let is_closure = ty.bounds.iter().any(|bound| match bound {
+                    TypeParamBound::Trait(bound) => {
+                        let last = bound.path.segments.last().unwrap();
+                        ["Fn", "FnMut", "FnOnce"].iter().any(|f| last.ident == f)
+                    }
+                    _ => false,
+                });
+                return if is_closure {
+                    Some(Placeholder {
+                        text: "<closure>",
+                        options,
+                    })
+                } else {
+                    None
+                };
+            }
+            _ => return None,
+        };
+    }
+}
+
+struct Placeholder {
+    text: &'static str,
+    // How many Options the closure is inside of. Each prints as `None`, or as
+    // `Some(...)` around the rest.
+    options: usize,
+}
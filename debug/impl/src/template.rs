@@ -0,0 +1,120 @@
+use crate::ast::{Field, Variant};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Error, LitStr, Member, Result};
+
+// A format string whose placeholders refer to fields of one variant, such as
+// "{name} failed with {code:#x}", rewritten so that each placeholder names the
+// binding of its field instead.
+pub struct Template<'a> {
+    pub format: LitStr,
+    // Every field referenced by the template, without duplicates.
+    pub fields: Vec<&'a Field<'a>>,
+    // Each placeholder's field together with the formatting trait it requires.
+    pub uses: Vec<(&'a Field<'a>, TokenStream)>,
+}
+
+impl<'a> Template<'a> {
+    pub fn parse(lit: &LitStr, variant: &'a Variant<'a>) -> Result<Self> {
+        let value = lit.value();
+        let mut read = value.as_str();
+        let mut format = String::new();
+        let mut fields: Vec<&Field> = Vec::new();
+        let mut uses = Vec::new();
+
+        while let Some(i) = read.find(['{', '}']) {
+            format.push_str(&read[..i]);
+            let brace = &read[i..i + 1];
+            read = &read[i + 1..];
+            if read.starts_with(brace) {
+                format.push_str(brace);
+                format.push_str(brace);
+                read = &read[1..];
+                continue;
+            }
+            if brace == "}" {
+                return Err(Error::new(lit.span(), "unmatched `}` in format string"));
+            }
+
+            let end = read
+                .find('}')
+                .ok_or_else(|| Error::new(lit.span(), "unterminated `{` in format string"))?;
+            let placeholder = &read[..end];
+            read = &read[end + 1..];
+
+            let (arg, spec) = match placeholder.split_once(':') {
+                Some((arg, spec)) => (arg.trim(), Some(spec)),
+                None => (placeholder.trim(), None),
+            };
+            let field = lookup(lit, variant, arg)?;
+            if !fields.iter().any(|prev| prev.binding == field.binding) {
+                fields.push(field);
+            }
+            uses.push((field, format_trait(spec.unwrap_or(""))));
+
+            format.push('{');
+            format.push_str(&field.binding.to_string());
+            if let Some(spec) = spec {
+                format.push(':');
+                format.push_str(spec);
+            }
+            format.push('}');
+        }
+        format.push_str(read);
+
+        Ok(Template {
+            format: LitStr::new(&format, lit.span()),
+            fields,
+            uses,
+        })
+    }
+
+    // Arguments to pass after the format string, one per referenced field.
+    // The bindings are references into `self`. They are passed dereferenced,
+    // which write! borrows again without moving, so that `{:p}` prints the
+    // field itself, like a raw pointer, rather than the address of the field.
+    pub fn args(&self) -> TokenStream {
+        let bindings = self.fields.iter().map(|field| &field.binding);
+        quote!(#(#bindings = *#bindings),*)
+    }
+}
+
+fn lookup<'a>(lit: &LitStr, variant: &'a Variant<'a>, arg: &str) -> Result<&'a Field<'a>> {
+    if arg.is_empty() {
+        return Err(Error::new(
+            lit.span(),
+            "format placeholders must refer to a field by name or index, like `{name}` or `{0}`",
+        ));
+    }
+    let found = variant.fields.iter().find(|field| match &field.member {
+        Member::Named(ident) => ident.unraw() == arg.trim_start_matches("r#"),
+        Member::Unnamed(index) => index.index.to_string() == arg,
+    });
+    found.ok_or_else(|| {
+        let msg = if arg.bytes().all(|b| b.is_ascii_digit()) {
+            format!("no field at index {} in `{}`", arg, variant.ident)
+        } else {
+            format!("no field named `{}` in `{}`", arg, variant.ident)
+        };
+        Error::new(lit.span(), msg)
+    })
+}
+
+// The formatting trait selected by the type at the end of a format spec, as in
+// `{:?}`, `{:#x}` or `{:>8}`.
+pub fn format_trait(spec: &str) -> TokenStream {
+    if spec.ends_with('?') {
+        return quote!(::core::fmt::Debug);
+    }
+    match spec.chars().last() {
+        Some('x') => quote!(::core::fmt::LowerHex),
+        Some('X') => quote!(::core::fmt::UpperHex),
+        Some('o') => quote!(::core::fmt::Octal),
+        Some('b') => quote!(::core::fmt::Binary),
+        Some('p') => quote!(::core::fmt::Pointer),
+        Some('e') => quote!(::core::fmt::LowerExp),
+        Some('E') => quote!(::core::fmt::UpperExp),
+        _ => quote!(::core::fmt::Display),
+    }
+}
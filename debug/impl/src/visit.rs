@@ -0,0 +1,49 @@
+use crate::ast::{Body, Input, Variant};
+use crate::expand;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(input)?;
+    let ident = input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = expand::where_clause(&input, false)?;
+
+    // Opaque types and unions have no fields that can be shown.
+    let opaque = input.attrs.opaque || matches!(input.data, Body::Union);
+    let body = if opaque || input.fields().next().is_none() {
+        quote!(let _ = __visitor;)
+    } else {
+        let arms = input.variants().iter().map(visit_variant);
+        quote!(match self { #(#arms)* })
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::derive_debug::VisitFields for #ident #ty_generics #where_clause {
+            fn visit_fields(&self, __visitor: &mut dyn ::derive_debug::FieldVisitor) {
+                #body
+            }
+        }
+    })
+}
+
+fn visit_variant(variant: &Variant) -> TokenStream {
+    let pattern = expand::pattern(variant);
+    let fields = variant.fields.iter().map(|field| {
+        let name = expand::field_name(field);
+        let value = expand::field_value(field, &field.binding);
+        let ty = field.ty;
+        expand::skip_if(
+            field,
+            quote! {
+                __visitor.visit_field(#name, #value, ::core::any::type_name::<#ty>());
+            },
+        )
+    });
+    quote! {
+        #pattern => {
+            #(#fields)*
+        }
+    }
+}
@@ -1,8 +1,231 @@
-use proc_macro::TokenStream;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, so the traits used by the generated code live here and
+// the derive macros are implemented in the separate derive_debug_impl crate.
+// Users only need to depend on this crate, which re-exports the macros.
+//
+// The crate is no_std so that the derives work in no_std crates too. Without
+// the default "std" feature there is no thread-local state to keep track of
+// nesting in, and #[debug(max_depth = N)] has no effect.
+#![no_std]
 
-#[proc_macro_derive(CustomDebug)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-    unimplemented!()
+pub use derive_debug_impl::{CustomDebug, CustomDisplay, DebugDiff, VisitFields};
+
+use alloc::string::String;
+use core::fmt::Debug;
+
+/// Receives the fields of a value one at a time, for example to emit them as
+/// structured key/value pairs.
+pub trait FieldVisitor {
+    /// Called once per field with its printed name, its value formatted
+    /// according to the field's `#[debug]` attributes, and the name of its
+    /// type as reported by [`core::any::type_name`].
+    fn visit_field(&mut self, name: &str, value: &dyn Debug, type_name: &'static str);
+}
+
+/// Types whose fields can be reported to a [`FieldVisitor`]. Implemented by
+/// `#[derive(VisitFields)]`.
+pub trait VisitFields {
+    fn visit_fields(&self, visitor: &mut dyn FieldVisitor);
+}
+
+/// Types that can describe how two of their values differ. Implemented by
+/// `#[derive(DebugDiff)]`.
+pub trait DebugDiff {
+    /// Lists the fields whose formatted values differ between `self` and
+    /// `other`, as a `-` line for the former and a `+` line for the latter.
+    /// Returns an empty string if the values print the same.
+    fn debug_diff(&self, other: &Self) -> String;
+}
+
+/// Types whose values can be viewed as plain bytes, as needed by
+/// `#[debug(bytes)]` to dump them in hex.
+///
+/// # Safety
+///
+/// Every byte of every value of the type must be initialized. This rules out
+/// types with padding, like `#[repr(C)] struct { a: u8, b: u32 }`, and unions
+/// with fields smaller than the union itself, since the remaining bytes are
+/// uninitialized while such a field is active.
+#[diagnostic::on_unimplemented(
+    message = "#[debug(bytes)] requires `{Self}` to implement `AsBytes`",
+    note = "implement it with `unsafe impl AsBytes for {Self} {{}}` if no value of the type has padding or other uninitialized bytes"
+)]
+pub unsafe trait AsBytes {}
+
+// Not public API. Used by the code generated by #[derive(CustomDebug)].
+#[doc(hidden)]
+pub mod __private {
+    use crate::AsBytes;
+    use core::fmt::{self, Debug, Formatter};
+    #[cfg(feature = "std")]
+    use std::cell::Cell;
+
+    pub use alloc::format;
+    pub use alloc::string::String;
+
+    pub fn bytes<T: AsBytes>(value: &T) -> &[u8] {
+        // SAFETY: by the contract of AsBytes, all size_of::<T>() bytes of the
+        // value are initialized.
+        unsafe {
+            core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>())
+        }
+    }
+
+    // The iterator over a field with #[debug(each = "...")]. Which of the two
+    // traits below provides `fmt_each` is decided by method resolution:
+    // calling it on `&&Each<I>` finds the impl for `&Each<I>` first, which
+    // applies if the items are key-value pairs as for maps, and falls back to
+    // the impl for `Each<I>` otherwise.
+    pub struct Each<I>(pub I);
+
+    pub trait EachEntry<V> {
+        fn fmt_each(
+            &self,
+            f: &mut Formatter,
+            each: impl Fn(&mut Formatter, &V) -> fmt::Result,
+        ) -> fmt::Result;
+    }
+
+    impl<I, K, V> EachEntry<V> for &Each<I>
+    where
+        I: Iterator<Item = (K, V)> + Clone,
+        K: Debug,
+    {
+        fn fmt_each(
+            &self,
+            f: &mut Formatter,
+            each: impl Fn(&mut Formatter, &V) -> fmt::Result,
+        ) -> fmt::Result {
+            let entries = self.0.clone().map(|(key, value)| {
+                let each = &each;
+                (key, fmt::from_fn(move |f| each(f, &value)))
+            });
+            f.debug_map().entries(entries).finish()
+        }
+    }
+
+    pub trait EachElement<T> {
+        fn fmt_each(
+            &self,
+            f: &mut Formatter,
+            each: impl Fn(&mut Formatter, &T) -> fmt::Result,
+        ) -> fmt::Result;
+    }
+
+    impl<I, T> EachElement<T> for Each<I>
+    where
+        I: Iterator<Item = T> + Clone,
+    {
+        fn fmt_each(
+            &self,
+            f: &mut Formatter,
+            each: impl Fn(&mut Formatter, &T) -> fmt::Result,
+        ) -> fmt::Result {
+            let entries = self.0.clone().map(|element| {
+                let each = &each;
+                fmt::from_fn(move |f| each(f, &element))
+            });
+            f.debug_list().entries(entries).finish()
+        }
+    }
+
+    // A field with #[debug(max_items = N)]. Like with `Each`, calling
+    // `fmt_items` on `&&Items<T>` iterates over `&T` if that is possible, as
+    // for collections, and otherwise over a clone of the field, as for ranges.
+    pub struct Items<'a, T: ?Sized>(pub &'a T);
+
+    pub trait ItemsByRef {
+        fn fmt_items(&self, f: &mut Formatter, max_items: usize) -> fmt::Result;
+    }
+
+    impl<'a, T: ?Sized> ItemsByRef for &Items<'a, T>
+    where
+        &'a T: IntoIterator,
+        <&'a T as IntoIterator>::Item: Debug,
+    {
+        fn fmt_items(&self, f: &mut Formatter, max_items: usize) -> fmt::Result {
+            fmt_items(f, self.0.into_iter(), max_items)
+        }
+    }
+
+    pub trait ItemsByValue {
+        fn fmt_items(&self, f: &mut Formatter, max_items: usize) -> fmt::Result;
+    }
+
+    impl<T> ItemsByValue for Items<'_, T>
+    where
+        T: IntoIterator + Clone,
+        T::Item: Debug,
+    {
+        fn fmt_items(&self, f: &mut Formatter, max_items: usize) -> fmt::Result {
+            fmt_items(f, self.0.clone().into_iter(), max_items)
+        }
+    }
+
+    fn fmt_items<I>(f: &mut Formatter, mut iter: I, max_items: usize) -> fmt::Result
+    where
+        I: Iterator,
+        I::Item: Debug,
+    {
+        let mut list = f.debug_list();
+        list.entries(iter.by_ref().take(max_items));
+        let rest = iter.count();
+        if rest > 0 {
+            list.entry(&format_args!("... ({} more)", rest));
+        }
+        list.finish()
+    }
+
+    #[cfg(feature = "std")]
+    std::thread_local! {
+        // How many CustomDebug values are being formatted on this thread, one
+        // inside the other, and the depth beyond which they print as `..`.
+        static DEPTH: Cell<(usize, usize)> = const { Cell::new((0, usize::MAX)) };
+    }
+
+    // One level of nesting, left again when dropped. Limits only ever get
+    // tighter further in, so a type with #[debug(max_depth = N)] caps the
+    // output of everything nested inside it.
+    pub struct Depth {
+        #[cfg(feature = "std")]
+        outer_limit: usize,
+    }
+
+    impl Depth {
+        #[cfg(feature = "std")]
+        pub fn enter(max_depth: Option<usize>) -> Option<Depth> {
+            DEPTH.with(|state| {
+                let (depth, outer_limit) = state.get();
+                let limit = match max_depth {
+                    Some(max_depth) => outer_limit.min(depth.saturating_add(max_depth)),
+                    None => outer_limit,
+                };
+                if depth >= limit {
+                    return None;
+                }
+                state.set((depth + 1, limit));
+                Some(Depth { outer_limit })
+            })
+        }
+
+        #[cfg(not(feature = "std"))]
+        pub fn enter(max_depth: Option<usize>) -> Option<Depth> {
+            let _ = max_depth;
+            Some(Depth {})
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Drop for Depth {
+        fn drop(&mut self) {
+            DEPTH.with(|state| {
+                let (depth, _) = state.get();
+                state.set((depth - 1, self.outer_limit));
+            });
+        }
+    }
 }
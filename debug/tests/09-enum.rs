@@ -0,0 +1,56 @@
+// Enums derive CustomDebug the same way the standard library's derive would
+// print them: unit variants by name, tuple variants through debug_tuple and
+// struct variants through debug_struct. Per-field format attributes apply
+// inside variants too, and bounds are inferred across all variants together.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub enum Error<T: Trait, U> {
+    Eof,
+    Io(&'static str, #[debug = "0x{:02x}"] u8),
+    Parse {
+        line: usize,
+        #[debug = "0b{:04b}"]
+        flags: u8,
+    },
+    Value(T::Value),
+    Marker(PhantomData<U>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Error<Id, NotDebug>>();
+    assert_debug::<Never>();
+
+    let eof = Error::<Id, NotDebug>::Eof;
+    assert_eq!(format!("{:?}", eof), "Eof");
+
+    let io = Error::<Id, NotDebug>::Io("read", 10);
+    assert_eq!(format!("{:?}", io), r#"Io("read", 0x0a)"#);
+
+    let parse = Error::<Id, NotDebug>::Parse { line: 3, flags: 5 };
+    assert_eq!(format!("{:?}", parse), "Parse { line: 3, flags: 0b0101 }");
+
+    let value = Error::<Id, NotDebug>::Value(7);
+    assert_eq!(format!("{:?}", value), "Value(7)");
+}
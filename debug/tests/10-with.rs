@@ -0,0 +1,51 @@
+// A field attribute #[debug(with = "path::to_fn")] formats that field by
+// calling `to_fn(&field, formatter)` instead of going through Debug. Fields
+// formatted this way do not contribute to the inferred trait bounds, so a type
+// parameter that only appears in such fields is left unbounded.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+mod hex {
+    use std::fmt;
+
+    pub fn dump(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn len<T>(values: &Vec<T>, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "[{} items]", values.len())
+}
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    id: u16,
+    #[debug(with = "hex::dump")]
+    payload: Vec<u8>,
+    #[debug(with = "len")]
+    buffer: Vec<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Packet<NotDebug>>();
+
+    let packet = Packet {
+        id: 7,
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        buffer: vec![NotDebug, NotDebug],
+    };
+
+    let debug = format!("{:?}", packet);
+    let expected = "Packet { id: 7, payload: deadbeef, buffer: [2 items] }";
+
+    assert_eq!(debug, expected);
+}
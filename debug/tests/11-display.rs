@@ -0,0 +1,71 @@
+// CustomDisplay is a sibling of CustomDebug that implements Display from a
+// #[display("...")] template on the struct, or on each variant of an enum.
+// Placeholders refer to fields by name or by index and may carry a format
+// spec. Bounds are inferred the same way as for CustomDebug, using whichever
+// formatting trait each placeholder's spec calls for.
+
+use derive_debug::CustomDisplay;
+use std::fmt::{Display, LowerHex};
+use std::io;
+
+#[derive(CustomDisplay)]
+#[display("{name} failed with {code:#x}")]
+pub struct Failure<C> {
+    name: &'static str,
+    code: C,
+}
+
+#[derive(CustomDisplay)]
+pub enum Error {
+    #[display("{0}")]
+    Io(io::Error),
+    #[display("unexpected {{ at line {line}, column {column}")]
+    Parse { line: usize, column: usize },
+    #[display("end of file")]
+    Eof,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0:?} ({0})")]
+pub struct Both<T>(T);
+
+#[derive(CustomDisplay)]
+#[display("at {0:p}")]
+pub struct At<T>(T);
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    // Implements LowerHex but not Display.
+    struct Hex;
+
+    impl LowerHex for Hex {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("0xhex")
+        }
+    }
+
+    assert_display::<Failure<Hex>>();
+
+    let failure = Failure {
+        name: "flash",
+        code: 255,
+    };
+    assert_eq!(failure.to_string(), "flash failed with 0xff");
+
+    let io = Error::Io(io::Error::other("disk full"));
+    assert_eq!(io.to_string(), "disk full");
+
+    let parse = Error::Parse { line: 4, column: 2 };
+    assert_eq!(parse.to_string(), "unexpected { at line 4, column 2");
+
+    assert_eq!(Error::Eof.to_string(), "end of file");
+
+    assert_eq!(Both("x").to_string(), "\"x\" (x)");
+
+    // Raw pointers implement Pointer but not Display.
+    assert_display::<At<*const u8>>();
+    let byte = 0u8;
+    let ptr: *const u8 = &byte;
+    assert_eq!(At(ptr).to_string(), format!("at {:p}", ptr));
+}
@@ -0,0 +1,55 @@
+// #[debug(transparent)] makes a single-field struct print exactly like its
+// field, which suits newtype wrappers. #[debug(compact)] keeps a type on one
+// line even when the surrounding output is pretty-printed with {:#?}.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Id(u32);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Flags {
+    #[debug = "0b{:04b}"]
+    bits: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(compact)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+pub struct Shape {
+    id: Id,
+    flags: Flags,
+    points: Vec<Point>,
+}
+
+fn main() {
+    let shape = Shape {
+        id: Id(7),
+        flags: Flags { bits: 5 },
+        points: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+    };
+
+    let debug = format!("{:?}", shape);
+    let expected =
+        "Shape { id: 7, flags: 0b0101, points: [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }] }";
+    assert_eq!(debug, expected);
+
+    let pretty = format!("{:#?}", shape);
+    let expected = "\
+Shape {
+    id: 7,
+    flags: 0b0101,
+    points: [
+        Point { x: 1, y: 2 },
+        Point { x: 3, y: 4 },
+    ],
+}";
+    assert_eq!(pretty, expected);
+}
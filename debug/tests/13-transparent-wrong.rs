@@ -0,0 +1,14 @@
+// #[debug(transparent)] only makes sense when there is exactly one field to
+// forward to. Anything else is rejected with an error pointing at the
+// attribute.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Pair {
+    a: u8,
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,42 @@
+// #[debug(rename = "...")] changes the name that gets printed for a struct, an
+// enum variant or a named field, without touching the Rust identifiers. It
+// composes with the #[debug = "..."] format attribute on the same field.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "struct pci_dev")]
+pub struct PciDev {
+    #[debug(rename = "vendor_id")]
+    vendor: u16,
+    #[debug(rename = "CLASS")]
+    #[debug = "0x{:06x}"]
+    class: u32,
+}
+
+#[derive(CustomDebug)]
+pub enum State {
+    #[debug(rename = "D0")]
+    Active,
+    #[debug(rename = "D3hot")]
+    Suspended {
+        #[debug(rename = "wake")]
+        wakeup: bool,
+    },
+}
+
+fn main() {
+    let dev = PciDev {
+        vendor: 0x8086,
+        class: 0x030000,
+    };
+
+    let debug = format!("{:?}", dev);
+    let expected = "struct pci_dev { vendor_id: 32902, CLASS: 0x030000 }";
+    assert_eq!(debug, expected);
+
+    assert_eq!(format!("{:?}", State::Active), "D0");
+
+    let suspended = State::Suspended { wakeup: true };
+    assert_eq!(format!("{:?}", suspended), "D3hot { wake: true }");
+}
@@ -0,0 +1,45 @@
+// A type parameter that only appears in the signature of a trait object, such
+// as the argument of `dyn Fn(T)`, has no influence on whether the field can be
+// printed. A trait object's Debug impl, when there is one, is the same for
+// every T, and there is no impl that a `T: Debug` bound could unlock.
+//
+//     #[derive(CustomDebug)]
+//     pub struct Handler<T> {
+//         name: &'static str,
+//         callback: Callback<dyn Fn(T)>,
+//     }
+//
+// Inferring `T: Debug` here would make Handler<T> unprintable for any
+// non-Debug event type T, for no reason. The expected impl is:
+//
+//     impl<T> Debug for Handler<T> {...}
+//
+// The same reasoning applies to any other trait object, e.g. the `Item = T` in
+// `dyn Iterator<Item = T>`.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+pub struct Callback<F: ?Sized>(Box<F>);
+
+impl<F: ?Sized> Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<callback>")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Handler<T> {
+    name: &'static str,
+    callback: Callback<dyn Fn(T)>,
+    iter: Callback<dyn Iterator<Item = T>>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Handler<NotDebug>>();
+}
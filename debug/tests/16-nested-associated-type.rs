@@ -0,0 +1,44 @@
+// Associated types are found no matter how deeply they are nested in a field's
+// type. Here `T::Assoc` sits inside an Rc inside an Option, and inside a tuple
+// and an array behind a Box, while the type parameter T itself is not
+// mentioned anywhere else.
+//
+//     #[derive(CustomDebug)]
+//     pub struct Node<T: Trait> {
+//         shared: Option<Rc<T::Assoc>>,
+//         children: Vec<Box<(T::Assoc, [T::Assoc; 2])>>,
+//     }
+//
+// The expected impl bounds the associated type once and leaves T alone:
+//
+//     impl<T: Trait> Debug for Node<T>
+//     where
+//         T::Assoc: Debug,
+//     {...}
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+pub trait Trait {
+    type Assoc;
+}
+
+#[derive(CustomDebug)]
+pub struct Node<T: Trait> {
+    shared: Option<Rc<T::Assoc>>,
+    children: Vec<Box<(T::Assoc, [T::Assoc; 2])>>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated type does.
+    struct Id;
+
+    impl Trait for Id {
+        type Assoc = u8;
+    }
+
+    assert_debug::<Node<Id>>();
+}
@@ -0,0 +1,59 @@
+// Fully qualified paths like `<T as Trait>::Item` name an associated type just
+// like `T::Item` does, and need the same treatment: the projection is bounded,
+// not the type parameter it projects out of.
+//
+//     #[derive(CustomDebug)]
+//     pub struct Batch<T: Trait> {
+//         first: <T as Trait>::Item,
+//         rest: Vec<<<T as Trait>::Item as Trait>::Item>,
+//     }
+//
+// The expected impl:
+//
+//     impl<T: Trait> Debug for Batch<T>
+//     where
+//         <T as Trait>::Item: Debug,
+//         <<T as Trait>::Item as Trait>::Item: Debug,
+//     {...}
+//
+// Adding `T: Debug` here would be wrong, since the caller's T is free not to
+// implement Debug. Qualified paths whose self type does not involve any type
+// parameter, such as `<u8 as Trait>::Item`, need no bound at all.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Item;
+}
+
+impl Trait for u8 {
+    type Item = u16;
+}
+
+impl Trait for u16 {
+    type Item = u32;
+}
+
+#[derive(CustomDebug)]
+pub struct Batch<T: Trait>
+where
+    T::Item: Trait,
+{
+    first: <T as Trait>::Item,
+    rest: Vec<<<T as Trait>::Item as Trait>::Item>,
+    fixed: <u8 as Trait>::Item,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated types do.
+    struct Id;
+
+    impl Trait for Id {
+        type Item = u8;
+    }
+
+    assert_debug::<Batch<Id>>();
+}
@@ -0,0 +1,35 @@
+// Function pointers implement Debug by printing their address, whatever their
+// argument and return types are:
+//
+//     impl<Ret, T> Debug for fn(T) -> Ret {...}
+//
+// So a type parameter that only appears in a function pointer's signature
+// needs no bound.
+//
+//     #[derive(CustomDebug)]
+//     pub struct Hook<T, U> {
+//         handler: fn(T) -> U,
+//         fallback: Option<fn(&T)>,
+//     }
+//
+// The expected impl:
+//
+//     impl<T, U> Debug for Hook<T, U> {...}
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Hook<T, U> {
+    handler: fn(T) -> U,
+    fallback: Option<fn(&T)>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Hook<NotDebug, NotDebug>>();
+}
@@ -0,0 +1,63 @@
+// The #[debug(bound = "...")] escape hatch from test 08 also works on a single
+// field. There it replaces only the bounds that would have been inferred from
+// that field's type, and inference carries on as usual for the other fields.
+// An empty bound means the field does not require any bounds.
+//
+//     #[derive(CustomDebug)]
+//     pub struct Wrapper<T: Trait, U, V> {
+//         #[debug(bound = "T::Value: Debug")]
+//         field: Field<T>,
+//         normal: U,
+//         #[debug(bound = "")]
+//         marker: Marker<V>,
+//     }
+//
+// The expected impl:
+//
+//     impl<T: Trait, U, V> Debug for Wrapper<T, U, V>
+//     where
+//         T::Value: Debug,
+//         U: Debug,
+//     {...}
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U, V> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+    #[debug(bound = "")]
+    marker: Marker<V>,
+}
+
+#[derive(CustomDebug)]
+struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+pub struct Marker<V>(Option<V>);
+
+impl<V> Debug for Marker<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Wrapper<Id, u8, Id>>();
+}
@@ -0,0 +1,57 @@
+// Unions can derive CustomDebug too. Since there is no way to tell which field
+// of a union is active, a union prints only its name, the same as any type
+// marked #[debug(opaque)]. Opaque types need no bounds on their parameters.
+//
+// Adding #[debug(bytes)] also dumps the raw bytes of the value in hex. This
+// reads the memory of the value directly, so it is only possible for types
+// whose bytes are all initialized, like the FFI union below. The type has to
+// promise that through an `unsafe impl` of the AsBytes trait.
+
+use derive_debug::{AsBytes, CustomDebug};
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[repr(C)]
+pub union Register {
+    word: u32,
+    bytes: [u8; 4],
+}
+
+#[derive(CustomDebug)]
+#[debug(bytes)]
+#[repr(C)]
+pub union Value {
+    int: u32,
+    float: f32,
+}
+
+// Both fields cover all four bytes of the union.
+unsafe impl AsBytes for Value {}
+
+#[derive(CustomDebug)]
+#[debug(opaque)]
+pub struct Handle<T> {
+    raw: *mut T,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Handle<NotDebug>>();
+
+    let register = Register { word: 1 };
+    assert_eq!(format!("{:?}", register), "Register { .. }");
+
+    let value = Value {
+        int: u32::from_be(0xdeadbeef),
+    };
+    assert_eq!(format!("{:?}", value), "Value(de ad be ef)");
+
+    let handle = Handle::<NotDebug> {
+        raw: std::ptr::null_mut(),
+    };
+    assert_eq!(format!("{:?}", handle), "Handle { .. }");
+}
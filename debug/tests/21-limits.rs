@@ -0,0 +1,54 @@
+// #[debug(max_items = N)] prints at most N items of any field whose reference
+// can be iterated, followed by a note saying how many more there were. Fields
+// that can only be iterated by value, like ranges, are iterated over a clone.
+// #[debug(max_len = N)] does the same for the characters of a string field.
+// The types of the fields stay the same.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    #[debug(max_items = 3)]
+    values: Vec<T::Value>,
+    #[debug(max_items = 2)]
+    set: BTreeSet<u8>,
+    #[debug(max_items = 4)]
+    short: [u8; 2],
+    #[debug(max_len = 5)]
+    name: String,
+    #[debug(max_len = 8)]
+    label: &'static str,
+    #[debug(max_items = 2)]
+    range: Range<u32>,
+}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u32;
+    }
+
+    let field = Field::<Id> {
+        values: (0..1_000_000).collect(),
+        set: BTreeSet::from([3, 1, 2]),
+        short: [1, 2],
+        name: "héllo world".to_owned(),
+        label: "register",
+        range: 10..20,
+    };
+
+    let debug = format!("{:?}", field);
+    let expected = concat!(
+        r#"Field { values: [0, 1, 2, ... (999997 more)], set: [1, 2, ... (1 more)], "#,
+        r#"short: [1, 2], name: "héllo"... (6 more), label: "register", "#,
+        r#"range: [10, 11, ... (8 more)] }"#,
+    );
+    assert_eq!(debug, expected);
+}
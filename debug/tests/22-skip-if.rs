@@ -0,0 +1,44 @@
+// #[debug(skip_if = "path::to_predicate")] leaves a field out of the output
+// whenever `predicate(&field)` returns true, so that empty or absent values do
+// not clutter the output of large structs. The predicate is evaluated each
+// time the value is formatted.
+
+use derive_debug::CustomDebug;
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    method: &'static str,
+    #[debug(skip_if = "Option::is_none")]
+    body: Option<String>,
+    #[debug(skip_if = "Vec::is_empty")]
+    headers: Vec<(&'static str, &'static str)>,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Tick(#[debug(skip_if = "is_zero")] u32, bool),
+}
+
+fn main() {
+    let get = Request {
+        method: "GET",
+        body: None,
+        headers: Vec::new(),
+    };
+    assert_eq!(format!("{:?}", get), r#"Request { method: "GET" }"#);
+
+    let post = Request {
+        method: "POST",
+        body: Some("{}".to_owned()),
+        headers: vec![("Accept", "*/*")],
+    };
+    let expected = r#"Request { method: "POST", body: Some("{}"), headers: [("Accept", "*/*")] }"#;
+    assert_eq!(format!("{:?}", post), expected);
+
+    assert_eq!(format!("{:?}", Event::Tick(0, true)), "Tick(true)");
+    assert_eq!(format!("{:?}", Event::Tick(3, true)), "Tick(3, true)");
+}
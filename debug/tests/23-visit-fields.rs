@@ -0,0 +1,63 @@
+// #[derive(VisitFields)] implements derive_debug::VisitFields, which reports
+// every field of a value to a derive_debug::FieldVisitor along with the name
+// of its type, for example to emit structured key/value pairs from a logger.
+// The same #[debug] attributes as for CustomDebug decide the printed name and
+// formatted value of each field and whether it is skipped.
+
+use derive_debug::{FieldVisitor, VisitFields};
+use std::fmt::Debug;
+
+#[derive(VisitFields)]
+pub struct Field<T> {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(rename = "val")]
+    value: T,
+    #[debug(skip_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[derive(VisitFields)]
+pub enum Shape {
+    Circle(f32),
+    Empty,
+}
+
+struct Json(Vec<String>);
+
+impl FieldVisitor for Json {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug, type_name: &'static str) {
+        self.0.push(format!(
+            "{:?}: [{:?}, {:?}]",
+            name,
+            format!("{:?}", value),
+            type_name
+        ));
+    }
+}
+
+fn main() {
+    let field = Field {
+        name: "F",
+        bitmask: 0b00011100,
+        value: 7u16,
+        note: None,
+    };
+
+    let mut json = Json(Vec::new());
+    field.visit_fields(&mut json);
+    assert_eq!(
+        json.0,
+        [
+            r#""name": ["\"F\"", "&str"]"#,
+            r#""bitmask": ["0b00011100", "u8"]"#,
+            r#""val": ["7", "u16"]"#,
+        ],
+    );
+
+    let mut json = Json(Vec::new());
+    Shape::Circle(1.5).visit_fields(&mut json);
+    Shape::Empty.visit_fields(&mut json);
+    assert_eq!(json.0, [r#""0": ["1.5", "f32"]"#]);
+}
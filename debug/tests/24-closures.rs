@@ -0,0 +1,77 @@
+// Closures do not implement Debug, so a field holding a trait object of one of
+// the Fn traits, such as `Box<dyn Fn()>` or `&dyn FnMut(u8)`, is printed as a
+// `<closure>` placeholder instead of failing to compile. The same goes for an
+// optional closure like `Option<Rc<dyn Fn()>>`, however many Options deep.
+// Function pointers and raw pointers to closures already implement Debug by
+// printing their address, whatever their signature.
+//
+// The placeholder can be turned off with #[debug(no_placeholder)], for example
+// when the trait object is known to implement Debug some other way.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+pub trait Callback: Fn() {}
+
+impl Debug for dyn Callback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<callback>")
+    }
+}
+
+impl<F: Fn()> Callback for F {}
+
+#[derive(CustomDebug)]
+pub struct Button<'a, T> {
+    label: &'static str,
+    on_click: Box<dyn Fn(T)>,
+    on_hover: Option<Rc<dyn Fn()>>,
+    on_drag: Option<Option<Box<dyn Fn()>>>,
+    on_drop: Option<Box<Option<&'a dyn Fn()>>>,
+    raw: *const dyn Fn(),
+    on_key: &'a mut dyn FnMut(u8),
+    handler: fn(&u8) -> bool,
+    #[debug(no_placeholder)]
+    on_close: Box<dyn Callback>,
+}
+
+fn is_zero(value: &u8) -> bool {
+    *value == 0
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Button<NotDebug>>();
+
+    let noop = || {};
+    let mut keys = Vec::new();
+    let button = Button {
+        label: "OK",
+        on_click: Box::new(|_: NotDebug| {}),
+        on_hover: Some(Rc::new(|| {})),
+        on_drag: Some(Some(Box::new(|| {}))),
+        on_drop: Some(Box::new(None)),
+        raw: &noop as &dyn Fn() as *const dyn Fn(),
+        on_key: &mut |key| keys.push(key),
+        handler: is_zero,
+        on_close: Box::new(|| {}),
+    };
+
+    let debug = format!("{:?}", button);
+    let handler = format!("{:?}", is_zero as fn(&u8) -> bool);
+    let raw = format!("{:?}", button.raw);
+    let expected = format!(
+        concat!(
+            r#"Button {{ label: "OK", on_click: <closure>, on_hover: Some(<closure>), "#,
+            r#"on_drag: Some(Some(<closure>)), on_drop: Some(None), raw: {}, "#,
+            r#"on_key: <closure>, handler: {}, on_close: <callback> }}"#,
+        ),
+        raw, handler,
+    );
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,53 @@
+// #[debug(fmt = "...")] on a struct, or on a variant of an enum, replaces the
+// usual debug_struct output with a template. Placeholders refer to fields by
+// name or index and can carry a format spec. The trait each placeholder needs
+// is what gets inferred as a bound, so `{code:#x}` requires LowerHex only.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, LowerHex};
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Point({x}, {y})")]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+pub enum Status<C> {
+    #[debug(fmt = "Error({code:#x})")]
+    Error {
+        code: C,
+        message: String,
+    },
+    #[debug(fmt = "{0:?} {{ok}}")]
+    Ok(&'static str),
+    Pending,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Implements LowerHex but not Debug.
+    struct Code;
+
+    impl LowerHex for Code {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("0xc0de")
+        }
+    }
+
+    assert_debug::<Status<Code>>();
+
+    let point = Point { x: 1, y: -2 };
+    assert_eq!(format!("{:?}", point), "Point(1, -2)");
+    assert_eq!(format!("{:#?}", vec![point]), "[\n    Point(1, -2),\n]");
+
+    let error = Status::Error {
+        code: 255,
+        message: "unused".to_owned(),
+    };
+    assert_eq!(format!("{:?}", error), "Error(0xff)");
+    assert_eq!(format!("{:?}", Status::<u8>::Ok("done")), r#""done" {ok}"#);
+    assert_eq!(format!("{:?}", Status::<u8>::Pending), "Pending");
+}
@@ -0,0 +1,13 @@
+// Placeholders in a #[debug(fmt = "...")] template must name a field of the
+// struct or variant. A misspelled name is reported at the template.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Point({x}, {z})")]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {}
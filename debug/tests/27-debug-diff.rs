@@ -0,0 +1,59 @@
+// #[derive(DebugDiff)] implements derive_debug::DebugDiff, whose debug_diff
+// method lists only the fields that print differently in two values, using
+// the same #[debug] attributes as CustomDebug. This keeps failing comparisons
+// of large structs readable in tests.
+
+use derive_debug::{CustomDebug, DebugDiff};
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Config {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    flags: u8,
+    retries: u32,
+    #[debug(skip_if = "Option::is_none")]
+    proxy: Option<&'static str>,
+    #[debug(rename = "peers")]
+    hosts: Vec<&'static str>,
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub enum Shape {
+    Circle { radius: u32 },
+    Square(u32),
+}
+
+fn main() {
+    let left = Config {
+        name: "prod",
+        flags: 0b00011100,
+        retries: 3,
+        proxy: None,
+        hosts: vec!["a", "b"],
+    };
+    let right = Config {
+        name: "prod",
+        flags: 0b00011101,
+        retries: 3,
+        proxy: Some("socks5://proxy"),
+        hosts: vec!["a", "c"],
+    };
+
+    assert_eq!(left.debug_diff(&left), "");
+    assert_eq!(
+        left.debug_diff(&right),
+        concat!(
+            "- flags: 0b00011100\n",
+            "+ flags: 0b00011101\n",
+            "+ proxy: Some(\"socks5://proxy\")\n",
+            "- peers: [\"a\", \"b\"]\n",
+            "+ peers: [\"a\", \"c\"]\n",
+        ),
+    );
+
+    let circle = Shape::Circle { radius: 1 };
+    let bigger = Shape::Circle { radius: 2 };
+    let square = Shape::Square(1);
+    assert_eq!(circle.debug_diff(&bigger), "- radius: 1\n+ radius: 2\n");
+    assert_eq!(circle.debug_diff(&square), "- Circle\n+ Square\n");
+}
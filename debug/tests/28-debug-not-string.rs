@@ -0,0 +1,13 @@
+// A format for a field must be given as a string literal, like the format
+// strings of the standard formatting macros.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug = 5]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Misspelled or unsupported attributes are reported along with the attributes
+// that are accepted in that position.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug(unknown)]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// The format string of #[debug = "..."] is given the value of the field as its
+// only argument, so it must contain exactly one placeholder.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "{:08b} ({:x})"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Unused {
+    #[debug = "hidden"]
+    secret: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Named {
+    #[debug = "{name:?}"]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// An attribute may be given at most once per field, even when split across
+// several #[debug] attributes.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    #[debug = "0b{:08b}"]
+    #[debug = "{:#x}"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Renamed {
+    #[debug(rename = "id")]
+    #[debug(rename = "ident")]
+    key: u32,
+}
+
+fn main() {}
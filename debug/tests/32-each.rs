@@ -0,0 +1,75 @@
+// #[debug(each = "...")] applies a format string to every element of a
+// collection instead of to the collection as a whole, keeping the list shape
+// of the collection's own Debug output. The values of maps are formatted the
+// same way while their keys print as usual. What counts as a map is decided by
+// whether iterating the field yields key-value pairs, not by the name of its
+// type. Generic elements are bounded by the trait their placeholder asks for,
+// and generic keys by Debug.
+
+use derive_debug::CustomDebug;
+use std::collections::{BTreeMap, HashMap};
+
+type Regs = BTreeMap<&'static str, u32>;
+
+// Not a map, despite the name.
+pub struct BitMap(Vec<u8>);
+
+impl BitMap {
+    fn iter(&self) -> std::slice::Iter<'_, u8> {
+        self.0.iter()
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Dump<'a> {
+    #[debug(each = "{:#04x}")]
+    registers: Vec<u8>,
+    #[debug(each = "0b{:04b}")]
+    flags: [u8; 2],
+    #[debug(each = "{:x}")]
+    window: &'a [u16],
+    #[debug(each = "{:#x}")]
+    addresses: BTreeMap<&'static str, u32>,
+    #[debug(each = "{:#x}")]
+    regs: Regs,
+    #[debug(each = "{:08b}")]
+    bits: BitMap,
+    #[debug(each = "{:?}")]
+    single: HashMap<u8, char>,
+}
+
+#[derive(CustomDebug)]
+pub struct Table<K, T> {
+    #[debug(each = "{:#x}")]
+    values: Vec<T>,
+    #[debug(each = "{:o}")]
+    named: BTreeMap<K, T>,
+}
+
+fn main() {
+    let dump = Dump {
+        registers: vec![0, 10, 255],
+        flags: [1, 6],
+        window: &[0xbeef, 0xcafe],
+        addresses: BTreeMap::from([("uart", 0x1000), ("gpio", 0x2000)]),
+        regs: BTreeMap::from([("ctrl", 0xff)]),
+        bits: BitMap(vec![5, 128]),
+        single: HashMap::from([(1, 'a')]),
+    };
+
+    let debug = format!("{:?}", dump);
+    let expected = concat!(
+        r#"Dump { registers: [0x00, 0x0a, 0xff], flags: [0b0001, 0b0110], "#,
+        r#"window: [beef, cafe], addresses: {"gpio": 0x2000, "uart": 0x1000}, "#,
+        r#"regs: {"ctrl": 0xff}, bits: [00000101, 10000000], single: {1: 'a'} }"#,
+    );
+    assert_eq!(debug, expected);
+
+    // `String` implements Debug but not Octal or LowerHex.
+    let table = Table {
+        values: vec![255u32],
+        named: BTreeMap::from([(String::from("mode"), 8u32)]),
+    };
+    let debug = format!("{:?}", table);
+    assert_eq!(debug, r#"Table { values: [0xff], named: {"mode": 10} }"#);
+}
@@ -0,0 +1,91 @@
+// #[debug(max_depth = N)] limits how many levels of nested CustomDebug values
+// get printed, counting the annotated value itself. Anything deeper prints as
+// `..`. Nested types share the budget of the outermost limit, and may only make
+// it tighter.
+
+use derive_debug::CustomDebug;
+use std::rc::Rc;
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub struct Tree {
+    value: u32,
+    children: Vec<Tree>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub enum Expr {
+    Lit(i64),
+    Neg(Box<Expr>),
+    Add(Rc<Expr>, Rc<Expr>),
+}
+
+#[derive(CustomDebug)]
+pub struct Node {
+    label: &'static str,
+    next: Option<Box<Node>>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 10)]
+pub struct Graph {
+    root: Node,
+}
+
+fn main() {
+    let leaf = |value| Tree {
+        value,
+        children: Vec::new(),
+    };
+    let tree = Tree {
+        value: 1,
+        children: vec![Tree {
+            value: 2,
+            children: vec![Tree {
+                value: 3,
+                children: vec![leaf(4), leaf(5)],
+            }],
+        }],
+    };
+    let debug = format!("{:?}", tree);
+    let expected = concat!(
+        "Tree { value: 1, children: [Tree { value: 2, children: [Tree { value: 3, ",
+        "children: [.., ..] }] }] }",
+    );
+    assert_eq!(debug, expected);
+
+    // Limits start from wherever the value is printed.
+    let debug = format!("{:?}", tree.children[0]);
+    let expected = concat!(
+        "Tree { value: 2, children: [Tree { value: 3, children: [Tree { value: 4, ",
+        "children: [] }, Tree { value: 5, children: [] }] }] }",
+    );
+    assert_eq!(debug, expected);
+
+    let lit = Rc::new(Expr::Lit(1));
+    let expr = Expr::Neg(Box::new(Expr::Add(lit.clone(), lit)));
+    assert_eq!(format!("{:?}", expr), "Neg(Add(.., ..))");
+
+    // Node has no limit of its own but is cut off by the Graph it is in.
+    let mut node = Node {
+        label: "end",
+        next: None,
+    };
+    for _ in 0..20 {
+        node = Node {
+            label: "link",
+            next: Some(Box::new(node)),
+        };
+    }
+    let graph = Graph { root: node };
+    let debug = format!("{:?}", graph);
+    assert!(debug.starts_with("Graph { root: Node { label: \"link\", next: Some(Node {"));
+    assert_eq!(debug.matches("Node {").count(), 9);
+    assert!(debug.contains("next: Some(..)"));
+    assert!(!debug.contains("\"end\""));
+
+    // Without a Graph around it, the whole list is printed.
+    let debug = format!("{:?}", graph.root);
+    assert_eq!(debug.matches("Node {").count(), 21);
+}
@@ -0,0 +1,122 @@
+// The generated impls refer to everything they use by absolute paths into
+// `core`, so they work in no_std crates and are not thrown off by items that
+// shadow the standard prelude or the traits involved. Their local variables
+// don't shadow functions named in #[debug(with = "...")] or #[debug(skip_if =
+// "...")] either.
+
+#![no_std]
+#![allow(dead_code, non_snake_case)]
+
+use core::fmt::{self as corefmt, Write};
+use derive_debug::{AsBytes, CustomDebug, CustomDisplay, DebugDiff, VisitFields};
+
+pub trait Debug {}
+pub trait Display {}
+pub struct Formatter;
+pub struct Result;
+pub struct Option;
+pub struct String;
+pub fn Some() {}
+pub fn None() {}
+pub fn Ok() {}
+pub fn Err() {}
+pub fn write() {}
+
+fn builder(value: &u8, f: &mut corefmt::Formatter) -> corefmt::Result {
+    write!(f, "<{}>", value)
+}
+
+fn f(value: &u8, f: &mut corefmt::Formatter) -> corefmt::Result {
+    write!(f, "[{}]", value)
+}
+
+fn visitor(value: &u32) -> bool {
+    *value == 0
+}
+
+#[derive(CustomDebug, CustomDisplay, DebugDiff, VisitFields)]
+#[debug(max_depth = 4)]
+#[display("{name}: {value:#x}")]
+pub struct Register<T> {
+    name: &'static str,
+    #[debug(with = "builder")]
+    width: u8,
+    #[debug(with = "f")]
+    offset: u8,
+    #[debug(skip_if = "visitor")]
+    value: u32,
+    #[debug(each = "{:02x}")]
+    bytes: [u8; 2],
+    #[debug(max_items = 1)]
+    history: [T; 2],
+    #[debug(max_len = 3)]
+    note: &'static str,
+}
+
+#[derive(CustomDebug, CustomDisplay, DebugDiff, VisitFields)]
+pub enum Access {
+    #[display("read-only")]
+    ReadOnly,
+    #[display("read-write {0}")]
+    ReadWrite(bool),
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Address(u32);
+
+#[derive(CustomDebug)]
+#[debug(bytes)]
+pub struct Raw(u16);
+
+unsafe impl AsBytes for Raw {}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Point({x}, {y})", compact)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+struct Buffer {
+    bytes: [u8; 256],
+    len: usize,
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> corefmt::Result {
+        let end = self.len + s.len();
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        corefmt::Result::Ok(())
+    }
+}
+
+impl Buffer {
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+fn main() {
+    let register = Register {
+        name: "ctrl",
+        width: 8,
+        offset: 4,
+        value: 0,
+        bytes: [1, 255],
+        history: [7, 9],
+        note: "enable",
+    };
+
+    let mut buffer = Buffer {
+        bytes: [0; 256],
+        len: 0,
+    };
+    write!(buffer, "{:?}", register).unwrap();
+    let expected = concat!(
+        r#"Register { name: "ctrl", width: <8>, offset: [4], bytes: [01, ff], "#,
+        r#"history: [7, ... (1 more)], note: "ena"... (3 more) }"#,
+    );
+    assert_eq!(buffer.as_str(), expected);
+}
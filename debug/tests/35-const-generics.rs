@@ -0,0 +1,75 @@
+// Lifetimes and const generic parameters pass through to the generated impls
+// unchanged. Only type parameters are ever bounded, so a const parameter used
+// as an array length adds no bounds of its own, while the element type of an
+// array like `[T; N]` is inferred the same way as any other field.
+
+use derive_debug::{CustomDebug, DebugDiff, VisitFields};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug, DebugDiff, VisitFields)]
+pub struct Ring<'a, T, const N: usize> {
+    items: [T; N],
+    head: usize,
+    name: &'a str,
+}
+
+#[derive(CustomDebug)]
+pub struct Pad<'a, T: 'a, const N: usize = 4> {
+    bytes: [u8; N],
+    #[debug(each = "{:#x}")]
+    words: [u16; N],
+    marker: PhantomData<&'a T>,
+}
+
+#[derive(CustomDebug)]
+pub enum Slot<'a, T, const N: usize> {
+    Inline([T; N]),
+    Borrowed(&'a [T]),
+    Empty,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct NotDebug;
+
+    assert_debug::<Pad<NotDebug>>();
+    assert_debug::<Pad<NotDebug, 0>>();
+
+    let ring = Ring {
+        items: [1, 2, 3],
+        head: 1,
+        name: "ring",
+    };
+    let debug = format!("{:?}", ring);
+    assert_eq!(debug, r#"Ring { items: [1, 2, 3], head: 1, name: "ring" }"#);
+
+    let other = Ring {
+        items: [1, 2, 4],
+        head: 1,
+        name: "ring",
+    };
+    assert_eq!(
+        ring.debug_diff(&other),
+        "- items: [1, 2, 3]\n+ items: [1, 2, 4]\n"
+    );
+
+    let pad = Pad::<(), 2> {
+        bytes: [0, 1],
+        words: [0xff, 0x100],
+        marker: PhantomData,
+    };
+    let debug = format!("{:?}", pad);
+    assert_eq!(
+        debug,
+        "Pad { bytes: [0, 1], words: [0xff, 0x100], marker: PhantomData<&()> }"
+    );
+
+    let items = ["a", "b"];
+    let slot = Slot::<&str, 2>::Borrowed(&items);
+    assert_eq!(format!("{:?}", slot), r#"Borrowed(["a", "b"])"#);
+    assert_eq!(format!("{:?}", Slot::Inline([1u8; 2])), "Inline([1, 1])");
+    assert_eq!(format!("{:?}", Slot::<u8, 0>::Empty), "Empty");
+}
@@ -0,0 +1,15 @@
+// #[debug(bytes)] is rejected for types that don't implement AsBytes, since
+// reading padding or other uninitialized bytes is undefined behavior. Here
+// there are three bytes of padding between the two fields.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(bytes)]
+#[repr(C)]
+pub struct Padded {
+    a: u8,
+    b: u32,
+}
+
+fn main() {}
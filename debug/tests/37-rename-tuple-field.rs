@@ -0,0 +1,14 @@
+// Tuple fields are printed without names, so renaming one is an error rather
+// than something only some of the derives would honor.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Pair(#[debug(rename = "first")] u8, u8);
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Key(#[debug(rename = "code")] u32),
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// Like #[debug(...)], each #[display(...)] key may be given only once, and an
+// unknown key is reported together with the ones that are accepted.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{0}")]
+#[display(bound = "T: core::fmt::Display")]
+#[display(bound = "T: Clone")]
+pub struct Twice<T>(T);
+
+#[derive(CustomDisplay)]
+#[display("{0}")]
+#[display(bonud = "T: core::fmt::Display")]
+pub struct Typo<T>(T);
+
+fn main() {}
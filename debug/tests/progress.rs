@@ -1,12 +1,42 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-with.rs");
+    t.pass("tests/11-display.rs");
+    t.pass("tests/12-transparent-compact.rs");
+    t.compile_fail("tests/13-transparent-wrong.rs");
+    t.pass("tests/14-rename.rs");
+    t.pass("tests/15-trait-object.rs");
+    t.pass("tests/16-nested-associated-type.rs");
+    t.pass("tests/17-qualified-path.rs");
+    t.pass("tests/18-fn-pointer.rs");
+    t.pass("tests/19-field-bound.rs");
+    t.pass("tests/20-opaque.rs");
+    t.pass("tests/21-limits.rs");
+    t.pass("tests/22-skip-if.rs");
+    t.pass("tests/23-visit-fields.rs");
+    t.pass("tests/24-closures.rs");
+    t.pass("tests/25-fmt.rs");
+    t.compile_fail("tests/26-fmt-unknown-field.rs");
+    t.pass("tests/27-debug-diff.rs");
+    t.compile_fail("tests/28-debug-not-string.rs");
+    t.compile_fail("tests/29-debug-unknown.rs");
+    t.compile_fail("tests/30-debug-placeholders.rs");
+    t.compile_fail("tests/31-debug-duplicate.rs");
+    t.pass("tests/32-each.rs");
+    t.pass("tests/33-max-depth.rs");
+    t.pass("tests/34-no-std.rs");
+    t.pass("tests/35-const-generics.rs");
+    t.compile_fail("tests/36-bytes-unsafe.rs");
+    t.compile_fail("tests/37-rename-tuple-field.rs");
+    t.compile_fail("tests/38-display-attrs.rs");
 }
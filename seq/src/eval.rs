@@ -0,0 +1,131 @@
+use crate::expand::Scope;
+use crate::parse::Value;
+use proc_macro2::{Span, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Error, Ident, LitInt, Result, Token};
+
+type Op = fn(i128, i128) -> Option<i128>;
+
+// Evaluates an integer expression made of literals, loop variables, parentheses
+// and the operators `+`, `-`, `*`, `/` and `%`, which have the same precedence
+// as in Rust. Stops at the first token that cannot continue the expression.
+pub fn expr(input: ParseStream, scope: &Scope) -> Result<i128> {
+    let mut value = term(input, scope)?;
+    loop {
+        let (span, op): (Span, Op) = if input.peek(Token![+]) {
+            (input.parse::<Token![+]>()?.span, i128::checked_add)
+        } else if input.peek(Token![-]) {
+            (input.parse::<Token![-]>()?.span, i128::checked_sub)
+        } else {
+            return Ok(value);
+        };
+        value = apply(op, value, term(input, scope)?, span)?;
+    }
+}
+
+fn term(input: ParseStream, scope: &Scope) -> Result<i128> {
+    let mut value = unary(input, scope)?;
+    loop {
+        let (span, op): (Span, Op) = if input.peek(Token![*]) {
+            (input.parse::<Token![*]>()?.span, i128::checked_mul)
+        } else if input.peek(Token![/]) {
+            (input.parse::<Token![/]>()?.span, i128::checked_div)
+        } else if input.peek(Token![%]) {
+            (input.parse::<Token![%]>()?.span, i128::checked_rem)
+        } else {
+            return Ok(value);
+        };
+        value = apply(op, value, unary(input, scope)?, span)?;
+    }
+}
+
+fn unary(input: ParseStream, scope: &Scope) -> Result<i128> {
+    if input.peek(Token![-]) {
+        let minus: Token![-] = input.parse()?;
+        return apply(i128::checked_sub, 0, unary(input, scope)?, minus.span);
+    }
+    if input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let value = expr(&content, scope)?;
+        if !content.is_empty() {
+            return Err(content.error("expected an operator"));
+        }
+        return Ok(value);
+    }
+    if input.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        return match scope.get(&ident).map(Value::int) {
+            Some(Some(value)) => Ok(value),
+            Some(None) => Err(Error::new(
+                ident.span(),
+                format!("`{}` is not an integer and cannot be computed with", ident),
+            )),
+            None => Err(Error::new(
+                ident.span(),
+                format!("`{}` is not a loop variable", ident),
+            )),
+        };
+    }
+    let lit: LitInt = input.parse()?;
+    lit.base10_parse()
+}
+
+fn apply(op: Op, a: i128, b: i128, span: Span) -> Result<i128> {
+    op(a, b).ok_or_else(|| Error::new(span, "arithmetic overflow or division by zero"))
+}
+
+// How a computed value is written when pasted into an identifier: a format
+// spec like those of `format!`, limited to a zero-padded width and a radix, as
+// in `02`, `x`, `X`, `b`, `o` or `04x`.
+pub struct Format {
+    pub span: Span,
+    width: usize,
+    radix: Option<char>,
+}
+
+impl Parse for Format {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let mut spec = String::new();
+        while !input.is_empty() {
+            spec += &input.parse::<TokenTree>()?.to_string();
+        }
+        let error = || {
+            Error::new(
+                span,
+                format!(
+                    "unsupported format spec `{}`, expected a zero-padded width like `02` \
+                     and/or one of `x`, `X`, `b` or `o`",
+                    spec,
+                ),
+            )
+        };
+
+        let (width, radix) = match spec.char_indices().last() {
+            Some((i, radix @ ('x' | 'X' | 'b' | 'o'))) => (&spec[..i], Some(radix)),
+            _ => (spec.as_str(), None),
+        };
+        let width = match width {
+            "" if radix.is_some() => 0,
+            // Padding with anything other than zeros can't be pasted into an
+            // identifier.
+            _ if width.starts_with('0') => width.parse().map_err(|_| error())?,
+            _ => return Err(error()),
+        };
+        Ok(Format { span, width, radix })
+    }
+}
+
+impl Format {
+    pub fn apply(&self, value: i128) -> String {
+        let width = self.width;
+        match self.radix {
+            Some('x') => format!("{:0width$x}", value),
+            Some('X') => format!("{:0width$X}", value),
+            Some('b') => format!("{:0width$b}", value),
+            Some('o') => format!("{:0width$o}", value),
+            _ => format!("{:0width$}", value),
+        }
+    }
+}
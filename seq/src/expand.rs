@@ -0,0 +1,513 @@
+use crate::eval::{self, Format};
+use crate::parse::{Seq, Value};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, TokenStream, TokenTree};
+use syn::parse::{ParseStream, Parser};
+use syn::{braced, Error, LitStr, Result, Token};
+
+pub fn expand(seq: &Seq) -> Result<TokenStream> {
+    expand_in(seq, None)
+}
+
+// If the body contains any `#(...)*` sections, only those are repeated and the
+// rest of the body is emitted once. Otherwise the whole body is repeated. If
+// this seq! is nested in the body of another, the variables of the outer one
+// stay visible unless shadowed.
+fn expand_in(seq: &Seq, outer: Option<&Scope>) -> Result<TokenStream> {
+    if has_sections(seq.body.clone()) {
+        return expand_sections(seq, seq.body.clone(), outer);
+    }
+    let mut expanded = TokenStream::new();
+    for values in &seq.iterations {
+        let scope = Scope::new(seq, values, outer);
+        expanded.extend(substitute(seq.body.clone(), &scope)?);
+    }
+    Ok(expanded)
+}
+
+// Sections inside of a nested seq! belong to that one instead, whether it is
+// expanded along with this one or left for the compiler.
+fn has_sections(stream: TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    (0..tokens.len()).any(|i| {
+        section(&tokens[i..]).is_some()
+            || match &tokens[i] {
+                TokenTree::Group(group) => {
+                    (i < 2 || invocation(&tokens[i - 2..]).is_none())
+                        && has_sections(group.stream())
+                }
+                _ => false,
+            }
+    })
+}
+
+// The contents of `#(...)*` at the start of `tokens`.
+fn section(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), TokenTree::Punct(star), ..]
+            if pound.as_char() == '#'
+                && group.delimiter() == Delimiter::Parenthesis
+                && star.as_char() == '*' =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+// Outside of the sections only the variables of an outer seq! have values.
+// Tokens there are collected into runs, so that pastes and nested invocations
+// stay in one piece, and substituted as a whole.
+fn expand_sections(seq: &Seq, stream: TokenStream, outer: Option<&Scope>) -> Result<TokenStream> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut expanded = TokenStream::new();
+    let mut run = TokenStream::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(group) = section(&tokens[i..]) {
+            expanded.extend(flush(&mut run, outer)?);
+            for values in &seq.iterations {
+                let scope = Scope::new(seq, values, outer);
+                expanded.extend(substitute(group.stream(), &scope)?);
+            }
+            i += 3;
+            continue;
+        }
+        if invocation(&tokens[i..]).is_some() {
+            run.extend(tokens[i..i + 3].iter().cloned());
+            i += 3;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Group(group) if has_sections(group.stream()) => {
+                expanded.extend(flush(&mut run, outer)?);
+                let stream = expand_sections(seq, group.stream(), outer)?;
+                expanded.extend([TokenTree::Group(respan(group, stream))]);
+            }
+            token => run.extend([token.clone()]),
+        }
+        i += 1;
+    }
+    expanded.extend(flush(&mut run, outer)?);
+    Ok(expanded)
+}
+
+fn flush(run: &mut TokenStream, outer: Option<&Scope>) -> Result<TokenStream> {
+    let run = std::mem::take(run);
+    match outer {
+        Some(outer) => substitute(run, outer),
+        None => Ok(run),
+    }
+}
+
+// The arguments of the `seq!(...)` invocation at `tokens[i..]`, if any.
+// Qualified paths like `seq::seq!(...)` are left for the compiler to expand.
+fn nested(tokens: &[TokenTree], i: usize) -> Option<&Group> {
+    if i > 0 && matches!(&tokens[i - 1], TokenTree::Punct(punct) if punct.as_char() == ':') {
+        return None;
+    }
+    invocation(&tokens[i..])
+}
+
+// The arguments of a `seq!(...)` invocation at the start of `tokens`, qualified
+// or not.
+fn invocation(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Ident(ident), TokenTree::Punct(bang), TokenTree::Group(group), ..]
+            if ident == "seq" && bang.as_char() == '!' =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+fn starts_statement(tokens: &[TokenTree], i: usize) -> bool {
+    match i.checked_sub(1).map(|i| &tokens[i]) {
+        None => true,
+        Some(TokenTree::Punct(punct)) => punct.as_char() == ';',
+        Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Brace,
+        Some(_) => false,
+    }
+}
+
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "union",
+    "impl",
+    "trait",
+    "type",
+    "const",
+    "static",
+    "mod",
+    "use",
+    "extern",
+    "macro_rules",
+    "unsafe",
+    "async",
+];
+
+// Whether an expansion is items or semicolon-terminated statements rather than
+// an expression, going by how it starts and ends since syn is built without
+// the parsers for items.
+fn is_items(stream: &TokenStream) -> bool {
+    let tokens: Vec<TokenTree> = stream.clone().into_iter().collect();
+    match tokens.last() {
+        None => return true,
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => return true,
+        _ => {}
+    }
+    // Skip attributes and visibility.
+    let mut rest = &tokens[..];
+    loop {
+        rest = match rest {
+            [TokenTree::Punct(pound), TokenTree::Group(_), rest @ ..] if pound.as_char() == '#' => {
+                rest
+            }
+            [TokenTree::Ident(vis), TokenTree::Group(group), rest @ ..]
+                if vis == "pub" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                rest
+            }
+            [TokenTree::Ident(vis), rest @ ..] if vis == "pub" => rest,
+            _ => break,
+        };
+    }
+    match rest {
+        // Blocks like `unsafe { ... }` and `const { ... }` are expressions.
+        [TokenTree::Ident(_), TokenTree::Group(group), ..]
+            if group.delimiter() == Delimiter::Brace =>
+        {
+            false
+        }
+        [TokenTree::Ident(ident), ..] => ITEM_KEYWORDS.iter().any(|keyword| ident == keyword),
+        _ => false,
+    }
+}
+
+// Expands a nested invocation right away, after substituting the outer
+// variables into the rest of its header so that they can be used as bounds
+// like in `seq!(I in 0..N { ... })`.
+fn expand_nested(group: &Group, outer: &Scope) -> Result<TokenStream> {
+    let mut tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return Err(Error::new(group.span(), "expected seq! body in braces")),
+    };
+    // The variables before `in` are new names, even if they shadow outer ones.
+    let split = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident == "in"))
+        .unwrap_or(0);
+    let source = tokens.split_off(split);
+    let mut input: TokenStream = tokens.iter().cloned().collect();
+    input.extend(substitute(source.into_iter().collect(), outer)?);
+
+    if is_deferred(group, outer) {
+        let vars = idents(tokens.into_iter().collect());
+        let scope = Scope {
+            vars: &vars,
+            values: None,
+            outer: Some(outer),
+            defer: true,
+        };
+        let stream = substitute(body.stream(), &scope)?;
+        input.extend([TokenTree::Group(respan(&body, stream))]);
+        let mut invocation = TokenStream::new();
+        invocation.extend([
+            TokenTree::Ident(Ident::new("seq", group.span())),
+            TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+            TokenTree::Group(respan(group, input)),
+        ]);
+        return Ok(invocation);
+    }
+
+    input.extend([TokenTree::Group(body)]);
+    let seq: Seq = syn::parse2(input)?;
+    expand_in(&seq, Some(outer))
+}
+
+// Invocations that cannot be expanded yet are left for the compiler, with the
+// outer variables substituted into them: those whose header mentions a macro
+// metavariable like `$n`, and any inside the body of a `macro_rules!`.
+fn is_deferred(group: &Group, scope: &Scope) -> bool {
+    let mut header: Vec<TokenTree> = group.stream().into_iter().collect();
+    header.pop();
+    scope.defers() || has_metavar(header.into_iter().collect())
+}
+
+fn has_metavar(stream: TokenStream) -> bool {
+    stream.into_iter().any(|token| match token {
+        TokenTree::Punct(punct) => punct.as_char() == '$',
+        TokenTree::Group(group) => has_metavar(group.stream()),
+        _ => false,
+    })
+}
+
+fn idents(stream: TokenStream) -> Vec<Ident> {
+    stream
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) => vec![ident],
+            TokenTree::Group(group) => idents(group.stream()),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+// Whether `stream` mentions a variable whose value is not known yet.
+fn has_unknown(stream: TokenStream, scope: &Scope) -> bool {
+    stream.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => scope.is_unknown(&ident),
+        TokenTree::Group(group) => has_unknown(group.stream(), scope),
+        _ => false,
+    })
+}
+
+// The values of the loop variables in one iteration.
+pub struct Scope<'a> {
+    vars: &'a [Ident],
+    // None for the variables of a nested seq! left for the compiler, which only
+    // get their values once it expands.
+    values: Option<&'a [Value]>,
+    outer: Option<&'a Scope<'a>>,
+    // Whether nested invocations are left for the compiler.
+    defer: bool,
+}
+
+impl<'a> Scope<'a> {
+    fn new(seq: &'a Seq, values: &'a [Value], outer: Option<&'a Scope<'a>>) -> Self {
+        Scope {
+            vars: &seq.vars,
+            values: Some(values),
+            outer,
+            defer: false,
+        }
+    }
+
+    pub fn get(&self, ident: &Ident) -> Option<&'a Value> {
+        match self.vars.iter().position(|var| var == ident) {
+            Some(i) => Some(&self.values?[i]),
+            None => self.outer?.get(ident),
+        }
+    }
+
+    fn is_unknown(&self, ident: &Ident) -> bool {
+        match self.vars.iter().position(|var| var == ident) {
+            Some(_) => self.values.is_none(),
+            None => self.outer.is_some_and(|outer| outer.is_unknown(ident)),
+        }
+    }
+
+    fn defers(&self) -> bool {
+        self.defer || self.outer.is_some_and(Scope::defers)
+    }
+}
+
+// Replaces the loop variables with their current values, pasting a value onto
+// the identifier before it where the two are joined by `~`, as in `f~N`.
+// Computed values can be substituted as `#{N * 4}` and pasted as `f~{N + 1}`,
+// optionally with a format spec like `f~{N:02}` or `f~{N:x}`.
+fn substitute(stream: TokenStream, scope: &Scope) -> Result<TokenStream> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    let mut expanded = TokenStream::new();
+    // Set from `macro_rules!` up to the body of the macro.
+    let mut macro_rules = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(group) = nested(&tokens, i) {
+            let nested = expand_nested(group, scope)?;
+            // The semicolon after `seq!(...);` in statement position ends an
+            // expression statement, but would be a stray item after items, or
+            // after statements that end in their own semicolon. An invocation
+            // left for the compiler keeps it either way.
+            let semi = matches!(tokens.get(i + 3), Some(TokenTree::Punct(punct)) if punct.as_char() == ';');
+            let items = !is_deferred(group, scope) && is_items(&nested);
+            expanded.extend(nested);
+            i += if semi && starts_statement(&tokens, i) && items {
+                4
+            } else {
+                3
+            };
+            continue;
+        }
+        if let TokenTree::Ident(ident) = &tokens[i] {
+            if ident == "macro_rules"
+                && matches!(tokens.get(i + 1), Some(TokenTree::Punct(bang)) if bang.as_char() == '!')
+            {
+                macro_rules = true;
+            }
+        }
+        // Computed values that need the variables of an invocation left for
+        // the compiler are computed by that invocation.
+        let unknown = |group: &Group| has_unknown(group.stream(), scope);
+        if let Some(group) = computed(&tokens[i..], '#').filter(|group| !unknown(group)) {
+            let (value, format) = compute(group, scope)?;
+            if let Some(format) = format {
+                return Err(Error::new(
+                    format.span,
+                    "a format spec only applies to values pasted into an identifier with `~{...}`",
+                ));
+            }
+            let mut literal = Literal::i128_unsuffixed(value);
+            literal.set_span(group.span());
+            expanded.extend([TokenTree::Literal(literal)]);
+            i += 2;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Group(group) if macro_rules => {
+                macro_rules = false;
+                let scope = Scope {
+                    vars: &[],
+                    values: Some(&[]),
+                    outer: Some(scope),
+                    defer: true,
+                };
+                let stream = substitute(group.stream(), &scope)?;
+                expanded.extend([TokenTree::Group(respan(group, stream))]);
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), scope)?;
+                expanded.extend([TokenTree::Group(respan(group, stream))]);
+            }
+            TokenTree::Ident(ident) if scope.is_unknown(ident) => {
+                expanded.extend([TokenTree::Ident(ident.clone())]);
+            }
+            TokenTree::Ident(ident) if pasted(&tokens[i + 1..]) => {
+                let mut name = match scope.get(ident) {
+                    Some(value) => paste_text(value, ident)?,
+                    None => ident.to_string(),
+                };
+                while pasted(&tokens[i + 1..]) {
+                    // Stop at anything only the compiler's expansion knows, and
+                    // leave the rest of the paste to it.
+                    let next = match &tokens[i + 2] {
+                        TokenTree::Ident(next) => scope.is_unknown(next),
+                        TokenTree::Group(group) => unknown(group),
+                        _ => false,
+                    };
+                    if next {
+                        break;
+                    }
+                    match &tokens[i + 2] {
+                        TokenTree::Ident(next) => match scope.get(next) {
+                            Some(value) => name += &paste_text(value, next)?,
+                            None => name += &next.to_string(),
+                        },
+                        TokenTree::Group(group) => match compute(group, scope)? {
+                            (value, Some(format)) => name += &format.apply(value),
+                            (value, None) => name += &value.to_string(),
+                        },
+                        _ => unreachable!(),
+                    }
+                    i += 2;
+                }
+                expanded.extend([TokenTree::Ident(paste(&name, ident)?)]);
+            }
+            TokenTree::Ident(ident) => match scope.get(ident) {
+                Some(Value::Int(value)) => {
+                    let mut literal = Literal::i128_unsuffixed(*value);
+                    literal.set_span(ident.span());
+                    expanded.extend([TokenTree::Literal(literal)]);
+                }
+                Some(Value::Literal(_, tokens) | Value::Tokens(tokens)) => {
+                    expanded.extend(tokens.clone());
+                }
+                None => expanded.extend([TokenTree::Ident(ident.clone())]),
+            },
+            token => expanded.extend([token.clone()]),
+        }
+        i += 1;
+    }
+    Ok(expanded)
+}
+
+// How a value reads as part of an identifier. String literals contribute their
+// contents, so `S in ["a", "b"]` pastes `Foo~S` into `Fooa` and `Foob`.
+fn paste_text(value: &Value, var: &Ident) -> Result<String> {
+    let tokens = match value {
+        Value::Int(value) | Value::Literal(value, _) => return Ok(value.to_string()),
+        Value::Tokens(tokens) => tokens,
+    };
+    let mut iter = tokens.clone().into_iter();
+    match (iter.next(), iter.next()) {
+        (Some(TokenTree::Ident(ident)), None) => Ok(ident.to_string()),
+        (Some(TokenTree::Literal(literal)), None) => {
+            match syn::parse2::<LitStr>(TokenTree::Literal(literal.clone()).into()) {
+                Ok(lit) => Ok(lit.value()),
+                Err(_) => Ok(literal.to_string()),
+            }
+        }
+        _ => Err(Error::new(
+            var.span(),
+            format!(
+                "cannot paste `{}` here, only list elements that are a single identifier or literal can be pasted",
+                var,
+            ),
+        )),
+    }
+}
+
+// Whether `tokens` start with `~` followed by an identifier or by a computed
+// value in braces.
+fn pasted(tokens: &[TokenTree]) -> bool {
+    matches!(tokens, [TokenTree::Punct(tilde), TokenTree::Ident(_), ..] if tilde.as_char() == '~')
+        || computed(tokens, '~').is_some()
+}
+
+// The braces of `#{...}` or `~{...}` at the start of `tokens`.
+fn computed(tokens: &[TokenTree], prefix: char) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(punct), TokenTree::Group(group), ..]
+            if punct.as_char() == prefix && group.delimiter() == Delimiter::Brace =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+// The value of `{expr}` or `{expr:spec}`, along with the spec if there is one.
+fn compute(group: &Group, scope: &Scope) -> Result<(i128, Option<Format>)> {
+    // Parsing the braces too lets errors at the end of the expression point at
+    // the closing brace.
+    let parser = |input: ParseStream| {
+        let content;
+        braced!(content in input);
+        let value = eval::expr(&content, scope)?;
+        let format = if content.peek(Token![:]) {
+            content.parse::<Token![:]>()?;
+            Some(content.parse()?)
+        } else {
+            None
+        };
+        if !content.is_empty() {
+            return Err(content.error("expected an operator"));
+        }
+        Ok((value, format))
+    };
+    parser.parse2(TokenStream::from(TokenTree::Group(group.clone())))
+}
+
+// The pasted identifier keeps the span of the identifier it starts with, so
+// errors about it point at the original code.
+fn paste(name: &str, first: &Ident) -> Result<Ident> {
+    match syn::parse_str::<Ident>(name) {
+        Ok(mut ident) => {
+            ident.set_span(first.span());
+            Ok(ident)
+        }
+        Err(_) => Err(Error::new(
+            first.span(),
+            format!("`{}` is not a valid identifier", name),
+        )),
+    }
+}
+
+fn respan(original: &Group, stream: TokenStream) -> Group {
+    let mut group = Group::new(original.delimiter(), stream);
+    group.set_span(original.span());
+    group
+}
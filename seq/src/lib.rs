@@ -1,8 +1,14 @@
+mod eval;
+mod expand;
+mod parse;
+
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
-
-    unimplemented!()
+    let seq = parse_macro_input!(input as parse::Seq);
+    expand::expand(&seq)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
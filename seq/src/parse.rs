@@ -0,0 +1,314 @@
+use proc_macro2::{Spacing, Span, TokenStream, TokenTree};
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{braced, bracketed, parenthesized, Error, Ident, LitInt, Result, Token};
+
+// `N in 0..8 { ... }` or `(R, C) in 0..4 * 0..4 { ... }`: the loop variables,
+// the values they take on together in each iteration, and the body to repeat.
+pub struct Seq {
+    pub vars: Vec<Ident>,
+    // One entry per iteration, holding one value per variable.
+    pub iterations: Vec<Vec<Value>>,
+    pub body: TokenStream,
+}
+
+#[derive(Clone)]
+pub enum Value {
+    Int(i128),
+    // An integer element of a list, like `1u8`, `0x10` or `-1`. It is
+    // substituted as written so that its suffix and radix carry over, and
+    // computed with and pasted by its value.
+    Literal(i128, TokenStream),
+    // Any other element of a list, like `u8` or `"a"`, substituted as is.
+    Tokens(TokenStream),
+}
+
+impl Value {
+    pub fn int(&self) -> Option<i128> {
+        match self {
+            Value::Int(value) | Value::Literal(value, _) => Some(*value),
+            Value::Tokens(_) => None,
+        }
+    }
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (vars, span): (Vec<Ident>, Span) = if input.peek(syn::token::Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            let vars = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+            if vars.is_empty() {
+                return Err(Error::new(paren.span.join(), "expected loop variables"));
+            }
+            (vars.into_iter().collect(), paren.span.join())
+        } else {
+            let var: Ident = input.parse()?;
+            let span = var.span();
+            (vec![var], span)
+        };
+        input.parse::<Token![in]>()?;
+
+        // Checked against the sequences rather than the iterations, so that
+        // a mismatch is reported even if there are no iterations.
+        let (iterations, arity) = iterations(input)?;
+        if arity != vars.len() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "expected values for {} variable{}, found {}",
+                    vars.len(),
+                    if vars.len() == 1 { "" } else { "s" },
+                    arity,
+                ),
+            ));
+        }
+
+        let content;
+        braced!(content in input);
+        let body: TokenStream = content.parse()?;
+        Ok(Seq {
+            vars,
+            iterations,
+            body,
+        })
+    }
+}
+
+// Either the cartesian product `a * b * ...` of sequences, iterated with the
+// last one changing fastest, or `zip(a, b, ...)` to go through several
+// sequences of the same length in lockstep. Also returns the number of
+// sequences, which is the number of values in each iteration.
+fn iterations(input: ParseStream) -> Result<(Vec<Vec<Value>>, usize)> {
+    if input.peek(Ident) && input.peek2(syn::token::Paren) {
+        let zip: Ident = input.parse()?;
+        if zip != "zip" {
+            return Err(Error::new(zip.span(), "expected `zip(...)` or a range"));
+        }
+        let content;
+        parenthesized!(content in input);
+        let mut sequences: Vec<Vec<Value>> = Vec::new();
+        while !content.is_empty() {
+            let span = content.span();
+            let values = sequence(&content)?;
+            if let Some(first) = sequences.first() {
+                if values.len() != first.len() {
+                    return Err(Error::new(
+                        span,
+                        format!(
+                            "zip(...) needs sequences of the same length, this one has {} values instead of {}",
+                            values.len(),
+                            first.len(),
+                        ),
+                    ));
+                }
+            }
+            sequences.push(values);
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        let len = sequences.first().map_or(0, Vec::len);
+        let iterations = (0..len)
+            .map(|i| sequences.iter().map(|values| values[i].clone()).collect())
+            .collect();
+        return Ok((iterations, sequences.len()));
+    }
+
+    let mut iterations = vec![Vec::new()];
+    let mut arity = 0;
+    loop {
+        let values = sequence(input)?;
+        iterations = iterations
+            .into_iter()
+            .flat_map(|iteration| {
+                values.iter().map(move |value| {
+                    let mut iteration = iteration.clone();
+                    iteration.push(value.clone());
+                    iteration
+                })
+            })
+            .collect();
+        arity += 1;
+        if !input.peek(Token![*]) {
+            return Ok((iterations, arity));
+        }
+        input.parse::<Token![*]>()?;
+    }
+}
+
+// A source of values, optionally followed by `step n`.
+fn sequence(input: ParseStream) -> Result<Vec<Value>> {
+    let mut values = source(input)?;
+    if input.peek(Ident) {
+        let keyword: Ident = input.parse()?;
+        if keyword != "step" {
+            return Err(Error::new(keyword.span(), "expected `step` or `{`"));
+        }
+        step_by(&mut values, input)?;
+    }
+    Ok(values.into_vec())
+}
+
+// The values of a source. Those of a range are only computed once all of its
+// `.rev()` and `.step_by(n)` calls have been applied, so that a wide range with
+// a large step, like a table of registers, doesn't go through every value in
+// between.
+enum Values {
+    // `len` integers starting at `first`, each `step` after the one before.
+    Range { first: i128, step: i128, len: i128 },
+    List(Vec<Value>),
+}
+
+impl Values {
+    fn rev(&mut self) {
+        match self {
+            Values::Range { first, step, len } => {
+                if *len > 0 {
+                    *first += *step * (*len - 1);
+                    *step = -*step;
+                }
+            }
+            Values::List(values) => values.reverse(),
+        }
+    }
+
+    fn step_by(&mut self, n: usize) {
+        match self {
+            Values::Range { step, len, .. } => {
+                let n = n as i128;
+                // With fewer than two values left the step no longer matters,
+                // so saturating cannot change the result.
+                *step = step.saturating_mul(n);
+                *len = (*len + n - 1) / n;
+            }
+            Values::List(values) => *values = values.drain(..).step_by(n).collect(),
+        }
+    }
+
+    fn into_vec(self) -> Vec<Value> {
+        match self {
+            Values::Range { first, step, len } => {
+                (0..len).map(|i| Value::Int(first + i * step)).collect()
+            }
+            Values::List(values) => values,
+        }
+    }
+}
+
+// One of `a..b`, `a..=b` or a list like `[u8, u16]`, or any of those in
+// parentheses followed by any number of `.rev()` and `.step_by(n)` calls, which
+// behave like the iterator adapters of the same name.
+fn source(input: ParseStream) -> Result<Values> {
+    if input.peek(syn::token::Bracket) {
+        return list(input).map(Values::List);
+    }
+    if !input.peek(syn::token::Paren) {
+        let span = input.span();
+        let start = int(input)?;
+        let inclusive = input.peek(Token![..=]);
+        if inclusive {
+            input.parse::<Token![..=]>()?;
+        } else {
+            input.parse::<Token![..]>()?;
+        }
+        let end = int(input)?;
+        let len = end
+            .checked_sub(start)
+            .and_then(|len| len.checked_add(inclusive as i128))
+            .ok_or_else(|| Error::new(span, "range is too large"))?;
+        return Ok(Values::Range {
+            first: start,
+            step: 1,
+            len: len.max(0),
+        });
+    }
+
+    let content;
+    parenthesized!(content in input);
+    let mut values = source(&content)?;
+    if !content.is_empty() {
+        return Err(content.error("unexpected token after range"));
+    }
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        let method: Ident = input.parse()?;
+        let args;
+        parenthesized!(args in input);
+        if method == "rev" && args.is_empty() {
+            values.rev();
+        } else if method == "step_by" {
+            step_by(&mut values, &args)?;
+            if !args.is_empty() {
+                return Err(args.error("unexpected token after step"));
+            }
+        } else {
+            return Err(Error::new(
+                method.span(),
+                "expected `.rev()` or `.step_by(n)`",
+            ));
+        }
+    }
+    Ok(values)
+}
+
+// Elements are separated by commas outside of any brackets, including the angle
+// brackets of types like `HashMap<K, V>`. Elements that are integers can be
+// computed with like the values of a range.
+fn list(input: ParseStream) -> Result<Vec<Value>> {
+    let content;
+    bracketed!(content in input);
+    let mut values = Vec::new();
+    while !content.is_empty() {
+        let mut tokens = TokenStream::new();
+        let mut depth = 0;
+        let mut arrow = false;
+        while !content.is_empty() && (depth > 0 || !content.peek(Token![,])) {
+            let token: TokenTree = content.parse()?;
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    // The `>` of `->` in types like `fn() -> u8`.
+                    '>' if arrow => {}
+                    '>' if depth > 0 => depth -= 1,
+                    _ => {}
+                }
+                arrow = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+            } else {
+                arrow = false;
+            }
+            tokens.extend([token]);
+        }
+        if tokens.is_empty() {
+            return Err(content.error("expected list element"));
+        }
+        values.push(match int.parse2(tokens.clone()) {
+            Ok(value) => Value::Literal(value, tokens),
+            Err(_) => Value::Tokens(tokens),
+        });
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+    Ok(values)
+}
+
+fn step_by(values: &mut Values, input: ParseStream) -> Result<()> {
+    let step: LitInt = input.parse()?;
+    let n = step.base10_parse::<usize>()?;
+    if n == 0 {
+        return Err(Error::new(step.span(), "step must be greater than zero"));
+    }
+    values.step_by(n);
+    Ok(())
+}
+
+fn int(input: ParseStream) -> Result<i128> {
+    let negative = input.peek(Token![-]);
+    if negative {
+        input.parse::<Token![-]>()?;
+    }
+    let lit: LitInt = input.parse()?;
+    let value = lit.base10_parse::<i128>()?;
+    Ok(if negative { -value } else { value })
+}
@@ -0,0 +1,47 @@
+// Besides plain ranges, the header accepts a range in parentheses followed by
+// `.step_by(n)` and `.rev()` calls, which produce the same values as the
+// iterator adapters of the same name, and the shorthand `a..b step n`.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(16) {
+    const REG~N: usize = N;
+});
+
+const OFFSETS: [usize; 4] = seq!(N in 0..64 step 16 { [#(REG~N,)*] });
+
+seq!(N in (0..4).rev() {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Countdown {
+        #(
+            T~N,
+        )*
+    }
+});
+
+const MIXED: [i32; 4] = seq!(N in (1..=10).rev().step_by(3) { [#(N,)*] });
+
+const NEGATIVE: [i32; 3] = seq!(N in (-4..2).step_by(2) { [#(N,)*] });
+
+// Only the values that are stepped to are produced, so ranges can span far more
+// values than could ever be expanded.
+const BANKS: [u32; 4] = seq!(N in (0x4000_0000..0x5000_0000).step_by(0x0400_0000) { [#(N,)*] });
+
+const WIDE: [u64; 4] = seq!(N in 0..0x1_0000_0000_0000 step 0x4000_0000_0000 { [#(N,)*] });
+
+const TOP: [u32; 4] = seq!(N in (0..=0xffff_ffff).rev().step_by(0x4000_0000) { [#(N,)*] });
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 16, 32, 48]);
+    assert_eq!(REG48, 48);
+
+    assert_eq!(Countdown::T3 as u8, 0);
+    assert_eq!(Countdown::T0 as u8, 3);
+
+    assert_eq!(MIXED, [10, 7, 4, 1]);
+    assert_eq!(NEGATIVE, [-4, -2, 0]);
+
+    assert_eq!(BANKS, [0x4000_0000, 0x4400_0000, 0x4800_0000, 0x4c00_0000]);
+    assert_eq!(WIDE[3], 0xc000_0000_0000);
+    assert_eq!(TOP, [0xffff_ffff, 0xbfff_ffff, 0x7fff_ffff, 0x3fff_ffff]);
+}
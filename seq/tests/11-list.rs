@@ -0,0 +1,76 @@
+// Instead of a range, the loop variable can go over a list of elements in
+// square brackets. Each element is substituted as written, whether it is a
+// type, an expression or a literal, and can be pasted into an identifier if it
+// is a single identifier or literal. The contents of string literals are
+// pasted without their quotes.
+
+use seq::seq;
+use std::collections::HashMap;
+
+pub trait Width {
+    const BITS: u32;
+}
+
+seq!(T in [u8, u16, u32, u64] {
+    impl Width for T {
+        const BITS: u32 = T::BITS;
+    }
+
+    fn max_~T() -> T {
+        T::MAX
+    }
+});
+
+seq!(S in ["uart", "gpio"] {
+    pub struct Driver~S;
+
+    impl Driver~S {
+        const NAME: &'static str = S;
+    }
+});
+
+// Types with commas inside angle brackets count as one element.
+const SIZES: [usize; 3] = seq!(T in [HashMap<u8, u16>, fn(u8, u8) -> u16, [u8; 3]] {
+    [#(std::mem::size_of::<T>(),)*]
+});
+
+// Integer elements paste like the values of a range.
+seq!(N in [1, 2, 4, 8] {
+    enum Lanes {
+        #(
+            X~N = N,
+        )*
+    }
+});
+
+// They are substituted as written though, keeping any suffix or radix, and
+// only computed with by value.
+seq!(X in [1u8, 300u16, 0x10u64, -1i8] {
+    fn types() -> [&'static str; 4] {
+        [#(type_of(X),)*]
+    }
+
+    const SUM: i32 = 0 #(+ #{X})*;
+});
+
+fn type_of<T>(_: T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+fn main() {
+    assert_eq!(<u16 as Width>::BITS, 16);
+    assert_eq!(<u64 as Width>::BITS, 64);
+    assert_eq!(max_u8(), 255);
+    assert_eq!(max_u32(), u32::MAX);
+
+    assert_eq!(Driveruart::NAME, "uart");
+    assert_eq!(Drivergpio::NAME, "gpio");
+
+    assert_eq!(SIZES[2], 3);
+
+    assert_eq!(types(), ["u8", "u16", "u64", "i8"]);
+    assert_eq!(SUM, 316);
+
+    assert_eq!(Lanes::X4 as u8, 4);
+    assert_eq!(Lanes::X8 as u8, 8);
+}
@@ -0,0 +1,10 @@
+// Only elements made of a single identifier or literal can be pasted into an
+// identifier. Anything else is reported at the loop variable.
+
+use seq::seq;
+
+seq!(T in [u8, Vec<u8>] {
+    struct Wrapper~T(T);
+});
+
+fn main() {}
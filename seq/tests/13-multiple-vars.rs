@@ -0,0 +1,52 @@
+// Several loop variables can be bound at once by listing them in parentheses.
+// Their values come either from the cartesian product `a * b` of sequences,
+// with the last one changing fastest, or from `zip(a, b)`, which goes through
+// sequences of the same length in lockstep. Both forms work with repeat
+// sections and pasting.
+
+use seq::seq;
+
+pub struct Matrix {
+    cells: [[u8; 3]; 2],
+}
+
+impl Matrix {
+    seq!((R, C) in 0..2 * 0..3 {
+        #(
+            pub fn m~R~C(&self) -> u8 {
+                self.cells[R][C]
+            }
+        )*
+    });
+}
+
+const PAIRS: [(u8, u8); 6] = seq!((R, C) in 0..2 * (0..3).rev() { [#((R, C),)*] });
+
+seq!((Name, Width) in zip([Byte, Half, Word, Double], [8, 16, 32, 64]) {
+    #[derive(Debug)]
+    enum Access {
+        #(
+            Name~Width = Width,
+        )*
+    }
+});
+
+const TRIPLES: usize =
+    seq!((A, B, C) in 0..2 * [1, 2] * 0..10 step 5 { 0 #(+ A * 100 + B * 10 + C)* });
+
+fn main() {
+    let matrix = Matrix {
+        cells: [[1, 2, 3], [4, 5, 6]],
+    };
+    assert_eq!(matrix.m00(), 1);
+    assert_eq!(matrix.m12(), 6);
+
+    assert_eq!(PAIRS, [(0, 2), (0, 1), (0, 0), (1, 2), (1, 1), (1, 0)]);
+
+    assert_eq!(Access::Byte8 as u8, 8);
+    assert_eq!(Access::Word32 as u8, 32);
+    assert_eq!(Access::Double64 as u8, 64);
+
+    // 10 + 15 + 20 + 25 + 110 + 115 + 120 + 125
+    assert_eq!(TRIPLES, 540);
+}
@@ -0,0 +1,23 @@
+// The number of loop variables has to match the number of values produced for
+// each iteration, even if there are no iterations. The sequences zipped
+// together have to be of the same length.
+
+use seq::seq;
+
+seq!((R, C) in 0..4 {
+    fn f~R~C() {}
+});
+
+seq!(N in zip(0..4, 0..4) {
+    fn g~N() {}
+});
+
+seq!((R, C) in 0..0 {
+    fn h~R~C() {}
+});
+
+seq!((A, B) in zip(0..4, [a, b, c]) {
+    fn k~A~B() {}
+});
+
+fn main() {}
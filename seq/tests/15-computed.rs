@@ -0,0 +1,48 @@
+// Inside the body, `#{...}` substitutes the value of an integer expression
+// computed from the loop variables, and `~{...}` pastes one into an
+// identifier. Expressions can use literals, parentheses and the operators
+// `+ - * / %` with their usual precedence.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    pub const REG~{N * 4}: usize = #{N * 4};
+});
+
+seq!(N in 0..3 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum State {
+        #(
+            S~N,
+        )*
+    }
+
+    impl State {
+        fn next(self) -> Self {
+            match self {
+                #(
+                    State::S~N => State::S~{(N + 1) % 3},
+                )*
+            }
+        }
+    }
+});
+
+const TABLE: [i32; 4] = seq!(N in 1..=4 { [#(#{-N * (N - 1) / 2},)*] });
+
+seq!((R, C) in 0..2 * 0..2 {
+    const CELL_~{R * 2 + C}: (usize, usize) = (R, C);
+});
+
+fn main() {
+    assert_eq!(REG0, 0);
+    assert_eq!(REG12, 12);
+
+    assert_eq!(State::S0.next(), State::S1);
+    assert_eq!(State::S2.next(), State::S0);
+
+    assert_eq!(TABLE, [0, -1, -3, -6]);
+
+    assert_eq!(CELL_2, (1, 0));
+    assert_eq!(CELL_3, (1, 1));
+}
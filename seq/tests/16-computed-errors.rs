@@ -0,0 +1,22 @@
+// Computed values can only refer to loop variables that hold integers, and are
+// checked for overflow and division by zero.
+
+use seq::seq;
+
+seq!(N in 0..2 {
+    const A~N: usize = #{N * M};
+});
+
+seq!(T in [u8, u16] {
+    const B: usize = #{T + 1};
+});
+
+seq!(N in 0..2 {
+    fn f~{N / (N - 1)}() {}
+});
+
+seq!(N in 0..2 {
+    const C: usize = #{N +};
+});
+
+fn main() {}
@@ -0,0 +1,42 @@
+// Values pasted with `~{...}` can take a format spec after a colon, like the
+// ones of `format!`: a zero-padded width such as `02`, a radix out of `x`, `X`,
+// `b` and `o`, or both as in `04x`. Zero-padding keeps generated names in the
+// same order numerically and alphabetically.
+
+// Hex digits pasted into constant names come out lowercase.
+#![allow(non_upper_case_globals)]
+
+use seq::seq;
+
+seq!(N in 0..12 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Irq {
+        #(
+            Irq~{N:02},
+        )*
+    }
+});
+
+seq!(N in 8..12 {
+    pub const REG~{N:x}: u32 = N;
+    pub const UPPER_~{N:X}: u32 = N;
+    pub const BITS_~{N:b}: u32 = N;
+    pub const OCT_~{N:o}: u32 = N;
+    pub const ADDR_~{N * 4:04x}: u32 = #{N * 4};
+});
+
+fn main() {
+    assert_eq!(Irq::Irq07 as u8, 7);
+    assert_eq!(Irq::Irq11 as u8, 11);
+
+    assert_eq!(REGa, 10);
+    assert_eq!(UPPER_B, 11);
+    assert_eq!(BITS_1001, 9);
+    assert_eq!(OCT_12, 10);
+    assert_eq!(ADDR_002c, 44);
+
+    let mut names: Vec<String> = seq!(N in 0..12 { vec![#(format!("{:?}", Irq::Irq~{N:02}),)*] });
+    names.sort();
+    assert_eq!(names[2], "Irq02");
+    assert_eq!(names[11], "Irq11");
+}
@@ -0,0 +1,18 @@
+// Format specs are limited to what can be part of an identifier, and only apply
+// to values that are pasted.
+
+use seq::seq;
+
+seq!(N in 0..2 {
+    const A~{N:3}: u8 = 0;
+});
+
+seq!(N in 0..2 {
+    const B~{N:e}: u8 = 0;
+});
+
+seq!(N in 0..2 {
+    const C~N: u8 = #{N:02};
+});
+
+fn main() {}
@@ -0,0 +1,88 @@
+// A seq! nested in the body of another is expanded along with it. The outer
+// variables can be used in the inner header, for example as a bound, and in the
+// inner body, unless the inner seq! binds a variable of the same name. Repeat
+// sections inside the inner body belong to the inner seq!.
+
+use seq::seq;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub trait Arity {
+    const ARITY: usize;
+    fn sum(&self) -> u64;
+}
+
+seq!(N in 1..=12 {
+    seq!(I in 0..N {
+        impl<#(T~I: Copy + Into<u64>,)*> Arity for (#(T~I,)*) {
+            const ARITY: usize = N;
+
+            fn sum(&self) -> u64 {
+                0 #(+ self.I.into())*
+            }
+        }
+    });
+});
+
+// Inner bounds can be computed from outer variables, and inner variables
+// shadow outer ones of the same name.
+seq!(N in 0..3 {
+    const ROW_~N: [usize; #{N + 1}] = seq!(I in 0..=N { [#(I * 10 + N,)*] });
+    const SHADOW_~N: usize = seq!(N in 0..2 { 0 #(+ N)* });
+});
+
+// The outer body can have repeat sections of its own next to the inner ones.
+seq!(R in 0..3 {
+    const GRID: [[u8; 2]; 3] = [
+        #(
+            seq!(C in 0..2 { [#(R * 2 + C,)*] }),
+        )*
+    ];
+});
+
+// A qualified `seq::seq!` is expanded by the compiler rather than along with
+// the outer seq!, but its repeat sections still belong to it, and the outer
+// variables are substituted into it beforehand.
+seq!(N in 0..2 {
+    const QUALIFIED_~N: u32 = seq::seq!(I in 0..3 { N #(+ I)* });
+});
+
+seq!(N in 0..3 {
+    const TRIANGLE: [u32; 3] = [#(seq::seq!(I in 0..=N { 0 #(+ I)* }),)*];
+});
+
+// A nested seq! followed by a semicolon in a block is an expression statement
+// when it expands to an expression, and its value is discarded.
+static CALLS: AtomicU32 = AtomicU32::new(0);
+
+fn side(i: u32) -> u32 {
+    CALLS.fetch_add(1, Ordering::Relaxed);
+    i
+}
+
+seq!(N in 0..2 {
+    fn count~N() {
+        seq!(I in 0..3 { side(N #(+ side(I))*) });
+    }
+});
+
+fn main() {
+    assert_eq!(<(u8,)>::ARITY, 1);
+    assert_eq!((1u8, 2u16, 3u32).sum(), 6);
+    assert_eq!(
+        <(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)>::ARITY,
+        12
+    );
+
+    assert_eq!(ROW_0, [0]);
+    assert_eq!(ROW_2, [2, 12, 22]);
+    assert_eq!(SHADOW_2, 1);
+
+    assert_eq!(GRID, [[0, 1], [2, 3], [4, 5]]);
+
+    assert_eq!(QUALIFIED_1, 4);
+    assert_eq!(TRIANGLE, [0, 1, 3]);
+
+    count0();
+    count1();
+    assert_eq!(CALLS.load(Ordering::Relaxed), 8);
+}
@@ -0,0 +1,47 @@
+// A seq! nested in the body of a macro_rules! defined by another seq! cannot be
+// expanded along with the outer one, since its header may depend on the
+// arguments of the macro, like the bound `$n` below. It is left for the
+// compiler to expand wherever the macro is called, with the outer variables
+// already substituted into it. The same goes for a nested seq! whose header
+// mentions a metavariable.
+
+use seq::seq;
+
+seq!(N in 1..3 {
+    macro_rules! sum~N {
+        ($n:literal) => {
+            seq!(I in 0..$n { 0 #(+ I * N)* })
+        };
+    }
+
+    macro_rules! consts~N {
+        () => {
+            seq!(I in 0..2 {
+                const C~N~I: usize = #{I * 10} + N;
+            });
+        };
+    }
+
+    macro_rules! shadow~N {
+        () => {
+            seq!(N in 0..4 { 0 #(+ N)* })
+        };
+    }
+});
+
+consts1!();
+consts2!();
+
+fn main() {
+    assert_eq!(sum1!(3), 3);
+    assert_eq!(sum2!(3), 6);
+    assert_eq!(sum2!(0), 0);
+
+    assert_eq!(C10, 1);
+    assert_eq!(C11, 11);
+    assert_eq!(C20, 2);
+    assert_eq!(C21, 12);
+
+    assert_eq!(shadow1!(), 6);
+    assert_eq!(shadow2!(), 6);
+}
@@ -1,13 +1,24 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-init-array.rs");
-    //t.pass("tests/07-inclusive-range.rs");
-    //t.compile_fail("tests/08-ident-span.rs");
-    //t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-init-array.rs");
+    t.pass("tests/07-inclusive-range.rs");
+    t.compile_fail("tests/08-ident-span.rs");
+    t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-step-and-rev.rs");
+    t.pass("tests/11-list.rs");
+    t.compile_fail("tests/12-list-paste-error.rs");
+    t.pass("tests/13-multiple-vars.rs");
+    t.compile_fail("tests/14-variable-count.rs");
+    t.pass("tests/15-computed.rs");
+    t.compile_fail("tests/16-computed-errors.rs");
+    t.pass("tests/17-paste-format.rs");
+    t.compile_fail("tests/18-paste-format-errors.rs");
+    t.pass("tests/19-nested.rs");
+    t.pass("tests/20-nested-in-macro-rules.rs");
 }